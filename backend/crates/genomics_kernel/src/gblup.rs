@@ -3,6 +3,8 @@ use rayon::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
+use bijmantra_genomics::NdArray;
+
 const MAX_GENOTYPE: u8 = 2;
 const PLOIDY: f32 = 2.0;
 
@@ -64,48 +66,70 @@ pub fn calculate_g_matrix(markers: &[u8], n_markers: usize, n_individuals: usize
         return vec![0.0_f32; g_len];
     }
 
-    let mut z = vec![0.0_f32; expected_len];
-    for ind_idx in 0..n_individuals {
-        for marker_idx in 0..n_markers {
-            let idx = ind_idx * n_markers + marker_idx;
-            let geno = markers[idx];
-            if geno <= MAX_GENOTYPE {
-                z[idx] = geno as f32 - PLOIDY * freqs[marker_idx];
-            }
+    // Build genotype and validity-mask arrays, then center via one
+    // broadcasting subtraction (`geno - 2*freq`) instead of a hand-rolled
+    // per-element loop; the mask zeroes out genotypes that were excluded
+    // from the allele-frequency/scale calculation above.
+    let mut geno_f32 = vec![0.0_f32; expected_len];
+    let mut mask = vec![0.0_f32; expected_len];
+    for (idx, &geno) in markers.iter().enumerate() {
+        if geno <= MAX_GENOTYPE {
+            geno_f32[idx] = geno as f32;
+            mask[idx] = 1.0;
         }
     }
 
-    let mut g = vec![0.0_f32; g_len];
+    let geno_nd = NdArray::from_shape(geno_f32, vec![n_individuals, n_markers])
+        .expect("geno_f32 length matches n_individuals * n_markers");
+    let mask_nd = NdArray::from_shape(mask, vec![n_individuals, n_markers])
+        .expect("mask length matches n_individuals * n_markers");
+    let two_freqs_nd = NdArray::from_shape(
+        freqs.iter().map(|f| f * PLOIDY).collect(),
+        vec![1, n_markers],
+    )
+    .expect("freqs length matches n_markers");
+
+    let centered = geno_nd
+        .broadcast_op(&two_freqs_nd, |g, tf| g - tf)
+        .expect("(n_individuals, n_markers) broadcasts against (1, n_markers)");
+    let z = centered
+        .broadcast_op(&mask_nd, |c, m| c * m)
+        .expect("centered and mask share the same shape")
+        .to_vec();
+
+    let mut raw = vec![0.0_f32; g_len];
     #[cfg(not(target_arch = "wasm32"))]
     {
-        g.par_chunks_mut(n_individuals)
+        raw.par_chunks_mut(n_individuals)
             .enumerate()
             .for_each(|(i, row)| {
                 for j in 0..n_individuals {
                     let mut sum = 0.0_f32;
-                    let row_offset = i * n_markers;
-                    let col_offset = j * n_markers;
                     for k in 0..n_markers {
-                        sum += z[row_offset + k] * z[col_offset + k];
+                        sum += z[i * n_markers + k] * z[j * n_markers + k];
                     }
-                    row[j] = sum / scale;
+                    row[j] = sum;
                 }
             });
     }
     #[cfg(target_arch = "wasm32")]
     {
-        for (i, row) in g.chunks_mut(n_individuals).enumerate() {
+        for (i, row) in raw.chunks_mut(n_individuals).enumerate() {
             for j in 0..n_individuals {
                 let mut sum = 0.0_f32;
-                let row_offset = i * n_markers;
-                let col_offset = j * n_markers;
                 for k in 0..n_markers {
-                    sum += z[row_offset + k] * z[col_offset + k];
+                    sum += z[i * n_markers + k] * z[j * n_markers + k];
                 }
-                row[j] = sum / scale;
+                row[j] = sum;
             }
         }
     }
 
-    g
+    let raw_nd = NdArray::from_shape(raw, vec![n_individuals, n_individuals])
+        .expect("raw length matches n_individuals * n_individuals");
+    let scale_nd = NdArray::from_shape(vec![scale], vec![1]).expect("scalar shape is always valid");
+    raw_nd
+        .broadcast_op(&scale_nd, |x, s| x / s)
+        .expect("(n_individuals, n_individuals) broadcasts against a scalar")
+        .to_vec()
 }
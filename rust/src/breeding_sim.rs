@@ -0,0 +1,437 @@
+//! Forward-in-time recurrent selection simulator
+//! Discrete-generation breeding program projection: mutation, recombination,
+//! selection, and mating, so users can compare selection schemes before
+//! committing to a real program.
+
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::matrix::gaussian_sample;
+
+/// One simulated diploid individual: two haplotypes of `n_loci` 0/1 alleles.
+struct Individual {
+    hap1: Vec<u8>,
+    hap2: Vec<u8>,
+}
+
+impl Individual {
+    fn genomic_value(&self, effects: &[f64]) -> f64 {
+        (0..effects.len())
+            .map(|l| effects[l] * (self.hap1[l] + self.hap2[l]) as f64)
+            .sum()
+    }
+}
+
+/// Form a gamete from a parent via recombination: walk loci left to right,
+/// copying from one haplotype and switching to the other at each locus with
+/// probability `recombination_rate` (a simple uniform genetic map).
+fn make_gamete(parent: &Individual, recombination_rate: f64, rng: &mut impl Rng) -> Vec<u8> {
+    let n_loci = parent.hap1.len();
+    let mut gamete = vec![0u8; n_loci];
+    let mut from_hap1 = rng.gen::<bool>();
+
+    for l in 0..n_loci {
+        gamete[l] = if from_hap1 { parent.hap1[l] } else { parent.hap2[l] };
+        if rng.gen::<f64>() < recombination_rate {
+            from_hap1 = !from_hap1;
+        }
+    }
+
+    gamete
+}
+
+/// Apply de novo mutations: each locus in the gamete toggles (0<->1) with
+/// probability `mutation_rate`.
+fn mutate_gamete(gamete: &mut [u8], mutation_rate: f64, rng: &mut impl Rng) {
+    for allele in gamete.iter_mut() {
+        if rng.gen::<f64>() < mutation_rate {
+            *allele = 1 - *allele;
+        }
+    }
+}
+
+/// Per-generation summary of a [`simulate_breeding_program`] run
+#[derive(Serialize, Deserialize)]
+pub struct BreedingSimResult {
+    /// Population mean genomic (additive) value, one entry per generation
+    /// (including the starting generation 0, before any selection).
+    pub mean_genetic_value: Vec<f64>,
+    /// Population additive variance of genomic values, per generation.
+    pub additive_variance: Vec<f64>,
+    /// Mean inbreeding coefficient `F = 1 − Ho/He` averaged across loci, per
+    /// generation.
+    pub inbreeding_coefficient: Vec<f64>,
+    /// Allele-1 frequency trajectory, row-major `n_generations_recorded *
+    /// n_loci` (one row per recorded generation).
+    pub allele_freq_trajectory: Vec<f64>,
+    pub n_loci: usize,
+}
+
+/// Simulate a discrete-generation recurrent selection program.
+///
+/// Each individual carries a diploid haplotype of `n_loci` additive-effect
+/// loci (`effect_sizes`), initialized in Hardy-Weinberg equilibrium from
+/// `initial_freqs`. Each of `n_generations` rounds: (1) trait values are
+/// computed as the sum of locus effects plus `N(0, env_variance)`
+/// environmental noise; (2) the top `selection_proportion` fraction are
+/// selected — by truncation, or (if `fitness_proportional`) sampled with
+/// replacement with probability proportional to trait value minus the
+/// generation's minimum trait value (plus `1e-6`); (3) the next generation is
+/// formed by randomly mating selected parents (with probability
+/// `selfing_rate` a parent selfs instead of outcrossing), each parent
+/// contributing a gamete formed by recombination at `recombination_rate` per
+/// locus boundary and de novo mutation at `mutation_rate` per locus. Returns
+/// per-generation mean genetic value, additive variance, inbreeding
+/// coefficient, and allele-frequency trajectories (generation 0 is the
+/// initial, unselected population).
+#[wasm_bindgen]
+pub fn simulate_breeding_program(
+    n_individuals: usize,
+    n_loci: usize,
+    effect_sizes: &[f64],
+    initial_freqs: &[f64],
+    n_generations: usize,
+    selection_proportion: f64,
+    mutation_rate: f64,
+    recombination_rate: f64,
+    env_variance: f64,
+    selfing_rate: f64,
+    fitness_proportional: bool,
+) -> JsValue {
+    let mut rng = rand::thread_rng();
+
+    let mut population: Vec<Individual> = (0..n_individuals)
+        .map(|_| Individual {
+            hap1: (0..n_loci).map(|l| if rng.gen::<f64>() < initial_freqs[l] { 1 } else { 0 }).collect(),
+            hap2: (0..n_loci).map(|l| if rng.gen::<f64>() < initial_freqs[l] { 1 } else { 0 }).collect(),
+        })
+        .collect();
+
+    let mut mean_genetic_value = Vec::with_capacity(n_generations + 1);
+    let mut additive_variance = Vec::with_capacity(n_generations + 1);
+    let mut inbreeding_coefficient = Vec::with_capacity(n_generations + 1);
+    let mut allele_freq_trajectory = Vec::with_capacity((n_generations + 1) * n_loci);
+
+    let record_generation = |population: &[Individual],
+                              mean_genetic_value: &mut Vec<f64>,
+                              additive_variance: &mut Vec<f64>,
+                              inbreeding_coefficient: &mut Vec<f64>,
+                              allele_freq_trajectory: &mut Vec<f64>| {
+        let values: Vec<f64> = population.iter().map(|ind| ind.genomic_value(effect_sizes)).collect();
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let var = if values.len() > 1 {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        mean_genetic_value.push(mean);
+        additive_variance.push(var);
+
+        let mut total_f = 0.0;
+        for l in 0..n_loci {
+            let mut allele_sum = 0usize;
+            let mut het = 0usize;
+            for ind in population {
+                allele_sum += (ind.hap1[l] + ind.hap2[l]) as usize;
+                if ind.hap1[l] != ind.hap2[l] {
+                    het += 1;
+                }
+            }
+            let p = allele_sum as f64 / (2.0 * population.len() as f64);
+            allele_freq_trajectory.push(p);
+
+            let he = 2.0 * p * (1.0 - p);
+            let ho = het as f64 / population.len() as f64;
+            total_f += if he > 0.0 { 1.0 - ho / he } else { 0.0 };
+        }
+        inbreeding_coefficient.push((total_f / n_loci as f64).clamp(-1.0, 1.0));
+    };
+
+    record_generation(
+        &population,
+        &mut mean_genetic_value,
+        &mut additive_variance,
+        &mut inbreeding_coefficient,
+        &mut allele_freq_trajectory,
+    );
+
+    for _ in 0..n_generations {
+        let phenotypes: Vec<f64> = population.iter()
+            .map(|ind| ind.genomic_value(effect_sizes) + gaussian_sample(&mut rng) * env_variance.max(0.0).sqrt())
+            .collect();
+
+        let n_selected = ((n_individuals as f64 * selection_proportion).round() as usize).clamp(1, n_individuals);
+
+        let selected_idx: Vec<usize> = if fitness_proportional {
+            let min_p = phenotypes.iter().cloned().fold(f64::INFINITY, f64::min);
+            let weights: Vec<f64> = phenotypes.iter().map(|&p| p - min_p + 1e-6).collect();
+            let total_w: f64 = weights.iter().sum();
+            (0..n_selected)
+                .map(|_| {
+                    let mut target = rng.gen::<f64>() * total_w;
+                    let mut chosen = weights.len() - 1;
+                    for (idx, &w) in weights.iter().enumerate() {
+                        if target < w {
+                            chosen = idx;
+                            break;
+                        }
+                        target -= w;
+                    }
+                    chosen
+                })
+                .collect()
+        } else {
+            let mut order: Vec<usize> = (0..n_individuals).collect();
+            order.sort_by(|&a, &b| phenotypes[b].partial_cmp(&phenotypes[a]).unwrap());
+            order.into_iter().take(n_selected).collect()
+        };
+
+        let mut offspring = Vec::with_capacity(n_individuals);
+        for _ in 0..n_individuals {
+            let parent_a = &population[selected_idx[rng.gen_range(0..selected_idx.len())]];
+            let selfing = rng.gen::<f64>() < selfing_rate;
+            let parent_b = if selfing {
+                parent_a
+            } else {
+                &population[selected_idx[rng.gen_range(0..selected_idx.len())]]
+            };
+
+            let mut gamete1 = make_gamete(parent_a, recombination_rate, &mut rng);
+            let mut gamete2 = make_gamete(parent_b, recombination_rate, &mut rng);
+            mutate_gamete(&mut gamete1, mutation_rate, &mut rng);
+            mutate_gamete(&mut gamete2, mutation_rate, &mut rng);
+
+            offspring.push(Individual { hap1: gamete1, hap2: gamete2 });
+        }
+
+        population = offspring;
+
+        record_generation(
+            &population,
+            &mut mean_genetic_value,
+            &mut additive_variance,
+            &mut inbreeding_coefficient,
+            &mut allele_freq_trajectory,
+        );
+    }
+
+    let result = BreedingSimResult {
+        mean_genetic_value,
+        additive_variance,
+        inbreeding_coefficient,
+        allele_freq_trajectory,
+        n_loci,
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+/// Draw a Poisson(`lambda`) count via Knuth's algorithm — fine for the small
+/// `lambda` (mutations per haplotype per generation) this module needs;
+/// avoids pulling in `rand_distr` for a single call site.
+fn poisson_sample(lambda: f64, rng: &mut impl Rng) -> usize {
+    if lambda <= 0.0 {
+        return 0;
+    }
+    let l = (-lambda).exp();
+    let mut k = 0usize;
+    let mut p = 1.0;
+    loop {
+        k += 1;
+        p *= rng.gen::<f64>();
+        if p <= l {
+            break;
+        }
+    }
+    k - 1
+}
+
+/// Choose a parent by rejection sampling: repeatedly draw a uniform
+/// candidate and accept it with probability `fitness_i / max_fitness`. Falls
+/// back to a uniform draw if every individual has zero fitness (selection
+/// can't discriminate).
+fn select_by_fitness(fitness: &[f64], max_fitness: f64, rng: &mut impl Rng) -> usize {
+    if max_fitness <= 0.0 {
+        return rng.gen_range(0..fitness.len());
+    }
+    loop {
+        let candidate = rng.gen_range(0..fitness.len());
+        if rng.gen::<f64>() < fitness[candidate] / max_fitness {
+            return candidate;
+        }
+    }
+}
+
+/// Form a gamete by free recombination: each locus is drawn independently
+/// from one of the parent's two haplotypes (loci are unlinked, unlike
+/// [`make_gamete`]'s genetic-map-driven recombination).
+fn make_gamete_free(parent: &Individual, rng: &mut impl Rng) -> Vec<u8> {
+    (0..parent.hap1.len())
+        .map(|l| if rng.gen::<bool>() { parent.hap1[l] } else { parent.hap2[l] })
+        .collect()
+}
+
+/// Per-generation trajectory and final state of a [`simulate_population`] run
+#[derive(Serialize, Deserialize)]
+pub struct PopulationSimResult {
+    /// Population mean phenotype (= sum of active-locus additive effects),
+    /// one entry per generation including the starting generation 0.
+    pub mean_phenotype: Vec<f64>,
+    /// Additive genetic variance of phenotype, per generation.
+    pub additive_variance: Vec<f64>,
+    /// Expected heterozygosity (mean `2pq` across loci), per generation.
+    pub expected_heterozygosity: Vec<f64>,
+    /// Effect-allele frequency trajectory, row-major `n_generations_recorded
+    /// * n_loci` (one row per recorded generation).
+    pub allele_freq_trajectory: Vec<f64>,
+    /// Final generation's genotype matrix, row-major `n_individuals *
+    /// n_loci` dosages (0/1/2 copies of the effect allele).
+    pub final_genotypes: Vec<i32>,
+    pub n_loci: usize,
+}
+
+/// Forward-in-time Wright-Fisher simulation with Gaussian stabilizing
+/// selection toward `optimum`.
+///
+/// Each locus toggles an additive effect (`allele_effects[l]`) on or off per
+/// haplotype; phenotype is the sum of active effects. Each of `generations`
+/// rounds: (1) mutation — the number of new mutations per haplotype is
+/// drawn from `Poisson(mutation_rate * n_loci)`, each flipping a random
+/// locus's effect; (2) fitness is `exp(-(phenotype - optimum)^2 / (2 *
+/// selection_width^2))`; (3) parents are chosen by rejection sampling,
+/// accepting candidate `i` with probability `fitness_i / max_fitness`; (4)
+/// with probability `selfing_rate` the chosen parent selfs, otherwise a
+/// second parent is drawn the same way, and each contributes a gamete
+/// formed by free recombination (loci unlinked). `seed` makes the run
+/// reproducible. Returns per-generation diversity trajectories plus the
+/// final genotype matrix, for comparison against [`calculate_diversity`].
+#[wasm_bindgen]
+pub fn simulate_population(
+    n_individuals: usize,
+    n_loci: usize,
+    generations: usize,
+    mutation_rate: f64,
+    allele_effects: &[f64],
+    selection_width: f64,
+    selfing_rate: f64,
+    optimum: f64,
+    seed: u64,
+) -> JsValue {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut population: Vec<Individual> = (0..n_individuals)
+        .map(|_| Individual {
+            hap1: vec![0u8; n_loci],
+            hap2: vec![0u8; n_loci],
+        })
+        .collect();
+
+    let mut mean_phenotype = Vec::with_capacity(generations + 1);
+    let mut additive_variance = Vec::with_capacity(generations + 1);
+    let mut expected_heterozygosity = Vec::with_capacity(generations + 1);
+    let mut allele_freq_trajectory = Vec::with_capacity((generations + 1) * n_loci);
+
+    let record_generation = |population: &[Individual],
+                              mean_phenotype: &mut Vec<f64>,
+                              additive_variance: &mut Vec<f64>,
+                              expected_heterozygosity: &mut Vec<f64>,
+                              allele_freq_trajectory: &mut Vec<f64>| {
+        let phenotypes: Vec<f64> = population.iter().map(|ind| ind.genomic_value(allele_effects)).collect();
+        let n = phenotypes.len() as f64;
+        let mean = phenotypes.iter().sum::<f64>() / n;
+        let var = if phenotypes.len() > 1 {
+            phenotypes.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        mean_phenotype.push(mean);
+        additive_variance.push(var);
+
+        let mut total_he = 0.0;
+        for l in 0..n_loci {
+            let allele_sum: usize = population.iter().map(|ind| (ind.hap1[l] + ind.hap2[l]) as usize).sum();
+            let p = allele_sum as f64 / (2.0 * population.len() as f64);
+            allele_freq_trajectory.push(p);
+            total_he += 2.0 * p * (1.0 - p);
+        }
+        expected_heterozygosity.push(total_he / n_loci as f64);
+    };
+
+    record_generation(
+        &population,
+        &mut mean_phenotype,
+        &mut additive_variance,
+        &mut expected_heterozygosity,
+        &mut allele_freq_trajectory,
+    );
+
+    for _ in 0..generations {
+        // Mutation: flip a random locus's effect on/off per haplotype.
+        for ind in population.iter_mut() {
+            for hap in [&mut ind.hap1, &mut ind.hap2] {
+                let n_mutations = poisson_sample(mutation_rate * n_loci as f64, &mut rng);
+                for _ in 0..n_mutations {
+                    let locus = rng.gen_range(0..n_loci);
+                    hap[locus] = 1 - hap[locus];
+                }
+            }
+        }
+
+        // Gaussian stabilizing selection fitness.
+        let fitness: Vec<f64> = population
+            .iter()
+            .map(|ind| {
+                let phenotype = ind.genomic_value(allele_effects);
+                (-(phenotype - optimum).powi(2) / (2.0 * selection_width * selection_width)).exp()
+            })
+            .collect();
+        let max_fitness = fitness.iter().cloned().fold(0.0_f64, f64::max);
+
+        let mut offspring = Vec::with_capacity(n_individuals);
+        for _ in 0..n_individuals {
+            let parent_a_idx = select_by_fitness(&fitness, max_fitness, &mut rng);
+            let selfing = rng.gen::<f64>() < selfing_rate;
+            let parent_b_idx = if selfing {
+                parent_a_idx
+            } else {
+                select_by_fitness(&fitness, max_fitness, &mut rng)
+            };
+
+            let gamete1 = make_gamete_free(&population[parent_a_idx], &mut rng);
+            let gamete2 = make_gamete_free(&population[parent_b_idx], &mut rng);
+
+            offspring.push(Individual { hap1: gamete1, hap2: gamete2 });
+        }
+
+        population = offspring;
+
+        record_generation(
+            &population,
+            &mut mean_phenotype,
+            &mut additive_variance,
+            &mut expected_heterozygosity,
+            &mut allele_freq_trajectory,
+        );
+    }
+
+    let mut final_genotypes = vec![0i32; n_individuals * n_loci];
+    for (i, ind) in population.iter().enumerate() {
+        for l in 0..n_loci {
+            final_genotypes[i * n_loci + l] = (ind.hap1[l] + ind.hap2[l]) as i32;
+        }
+    }
+
+    let result = PopulationSimResult {
+        mean_phenotype,
+        additive_variance,
+        expected_heterozygosity,
+        allele_freq_trajectory,
+        final_genotypes,
+        n_loci,
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
@@ -11,6 +11,8 @@
 use std::ffi::c_int;
 use std::os::raw::c_double;
 
+use crate::matrix::SparseMatrix;
+
 /// FFI declarations for Fortran compute kernels
 #[link(name = "bijmantra_compute_c")]
 extern "C" {
@@ -49,6 +51,33 @@ extern "C" {
         max_iter: c_int,
     ) -> c_int;
 
+    /// Solve Mixed Model Equations using BiCGSTAB (handles non-symmetric
+    /// coefficient matrices that plain CG can't). Maintains the standard
+    /// two residual vectors (r, r̂) internally with scalars ρ, α, ω updated
+    /// per iteration.
+    fn solve_mme_bicgstab(
+        c: *const c_double,
+        rhs: *const c_double,
+        solution: *mut c_double,
+        dim: c_int,
+        tol: c_double,
+        max_iter: c_int,
+    ) -> c_int;
+
+    /// Solve Mixed Model Equations using restarted GMRES. Builds an Arnoldi
+    /// Krylov basis of size `restart`, solves the small least-squares
+    /// problem via Givens rotations, and restarts from the current
+    /// residual.
+    fn solve_mme_gmres(
+        c: *const c_double,
+        rhs: *const c_double,
+        solution: *mut c_double,
+        dim: c_int,
+        restart: c_int,
+        tol: c_double,
+        max_iter: c_int,
+    ) -> c_int;
+
     /// Compute Genomic Relationship Matrix (VanRaden Method 1)
     fn compute_grm_vanraden1(
         genotypes: *const c_double,
@@ -79,6 +108,77 @@ extern "C" {
         e: *mut c_double,
         n: c_int,
     ) -> c_int;
+
+    /// Estimate additive/residual variance components via REML (AI-REML or
+    /// EM-REML, selected by `method`). `var_a`/`var_e` are in/out: callers
+    /// pass starting values and the kernel overwrites them with the
+    /// converged estimates; `converged`/`iterations`/`log_lik` are out
+    /// parameters.
+    fn compute_reml(
+        y: *const c_double,
+        x: *const c_double,
+        z: *const c_double,
+        a: *const c_double,
+        var_a: *mut c_double,
+        var_e: *mut c_double,
+        method: c_int,
+        n: c_int,
+        p: c_int,
+        q: c_int,
+        max_iter: c_int,
+        tol: c_double,
+        converged: *mut c_int,
+        iterations: *mut c_int,
+        log_lik: *mut c_double,
+    ) -> c_int;
+
+    /// Bivariate AI-REML: jointly estimates the 2x2 genetic and residual
+    /// covariance matrices for two traits sharing the same relationship
+    /// matrix. `genetic_cov`/`residual_cov` are in/out 4-element row-major
+    /// 2x2 matrices (callers pass starting values). `constrain_cov_e`
+    /// zeroes the residual covariance (for traits measured on disjoint
+    /// individuals); `fix_rg_enabled`/`fix_rg` hold the genetic correlation
+    /// fixed at a supplied value (for likelihood-ratio testing) instead of
+    /// estimating it.
+    fn compute_bivar_reml(
+        y1: *const c_double,
+        y2: *const c_double,
+        x: *const c_double,
+        z: *const c_double,
+        a: *const c_double,
+        genetic_cov: *mut c_double,
+        residual_cov: *mut c_double,
+        constrain_cov_e: c_int,
+        fix_rg_enabled: c_int,
+        fix_rg: c_double,
+        n: c_int,
+        p: c_int,
+        q: c_int,
+        max_iter: c_int,
+        tol: c_double,
+        converged: *mut c_int,
+        iterations: *mut c_int,
+        log_lik: *mut c_double,
+    ) -> c_int;
+
+    /// Compute BLUP from a sparse (CSR) pedigree A-inverse, for pedigrees
+    /// too large to hold `a_inv` as a dense `q x q` array.
+    fn compute_blup_sparse(
+        y: *const c_double,
+        x: *const c_double,
+        z: *const c_double,
+        a_inv_values: *const c_double,
+        a_inv_col_indices: *const c_int,
+        a_inv_row_ptr: *const c_int,
+        a_inv_nnz: c_int,
+        var_a: c_double,
+        var_e: c_double,
+        beta: *mut c_double,
+        u: *mut c_double,
+        n: c_int,
+        p: c_int,
+        q: c_int,
+    ) -> c_int;
 }
 
 /// Error types for Fortran computations
@@ -193,6 +293,82 @@ pub fn blup(
     }
 }
 
+/// Safe wrapper for BLUP computation from a sparse (CSR) pedigree A-inverse.
+///
+/// Identical to [`blup`] except `a_inverse` is a [`SparseMatrix`] instead of
+/// a dense `q x q` slice, so pedigrees with millions of animals (where
+/// `a_inverse` is extremely sparse but infeasible to hold densely) never
+/// need the dense form materialized.
+///
+/// # Arguments
+/// * `phenotypes` - Phenotypic observations (n x 1)
+/// * `fixed_effects` - Fixed effects design matrix (n x p)
+/// * `random_effects` - Random effects design matrix (n x q)
+/// * `a_inverse` - Sparse inverse of the pedigree relationship matrix (q x q)
+/// * `var_additive` - Additive genetic variance
+/// * `var_residual` - Residual variance
+///
+/// # Returns
+/// * `BlupResult` containing fixed effects and breeding values
+pub fn blup_sparse(
+    phenotypes: &[f64],
+    fixed_effects: &[f64],
+    random_effects: &[f64],
+    a_inverse: &SparseMatrix,
+    var_additive: f64,
+    var_residual: f64,
+    n: usize,
+    p: usize,
+    q: usize,
+) -> ComputeResult<BlupResult> {
+    if phenotypes.len() != n {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if fixed_effects.len() != n * p {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if random_effects.len() != n * q {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if a_inverse.n_rows != q || a_inverse.n_cols != q {
+        return Err(ComputeError::InvalidDimensions);
+    }
+
+    let mut beta = vec![0.0; p];
+    let mut u = vec![0.0; q];
+
+    let col_indices: Vec<c_int> = a_inverse.col_indices.iter().map(|&c| c as c_int).collect();
+    let row_ptr: Vec<c_int> = a_inverse.row_ptr.iter().map(|&r| r as c_int).collect();
+
+    let status = unsafe {
+        compute_blup_sparse(
+            phenotypes.as_ptr(),
+            fixed_effects.as_ptr(),
+            random_effects.as_ptr(),
+            a_inverse.values.as_ptr(),
+            col_indices.as_ptr(),
+            row_ptr.as_ptr(),
+            a_inverse.nnz() as c_int,
+            var_additive,
+            var_residual,
+            beta.as_mut_ptr(),
+            u.as_mut_ptr(),
+            n as c_int,
+            p as c_int,
+            q as c_int,
+        )
+    };
+
+    match status {
+        0 => Ok(BlupResult {
+            beta,
+            breeding_values: u,
+        }),
+        -1 => Err(ComputeError::MatrixInversionFailed),
+        code => Err(ComputeError::Unknown(code)),
+    }
+}
+
 /// Safe wrapper for GBLUP computation
 /// 
 /// # Arguments
@@ -243,6 +419,241 @@ pub fn gblup(
     }
 }
 
+/// REML variance-component estimation method
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemlMethod {
+    /// Average-Information REML: Newton-Raphson-style updates using the
+    /// average-information matrix in place of the observed or expected
+    /// information, converging in far fewer rounds than EM-REML once near
+    /// the optimum.
+    AiReml,
+    /// Expectation-Maximization REML: the slower but unconditionally
+    /// stable fallback (see [`crate::statistics::estimate_heritability_reml`]
+    /// for a pure-Rust implementation of this method).
+    EmReml,
+}
+
+/// Variance-component estimation result
+#[derive(Debug, Clone)]
+pub struct RemlEstimateResult {
+    /// Additive genetic variance `σ²_a`.
+    pub var_additive: f64,
+    /// Residual variance `σ²_e`.
+    pub var_residual: f64,
+    /// `σ²_a / (σ²_a + σ²_e)`.
+    pub heritability: f64,
+    pub converged: bool,
+    pub iterations: i32,
+    pub log_likelihood: f64,
+}
+
+/// Estimate additive (`σ²_a`) and residual (`σ²_e`) variance components via
+/// REML, so `blup`/`gblup`/`solve_mme_pcg` callers don't have to supply them
+/// (or heritability) up front.
+///
+/// For `y = Xβ + Zu + e`, `u ~ N(0, Aσ²_a)`, `e ~ N(0, Iσ²_e)`,
+/// [`RemlMethod::AiReml`] iterates: form `V = ZAZ'σ²_a + Iσ²_e` and the
+/// projection `P = V⁻¹ − V⁻¹X(X'V⁻¹X)⁻¹X'V⁻¹`; the score for each component
+/// is `−0.5(tr(P·∂V/∂θᵢ) − y'P·∂V/∂θᵢ·Py)` with `∂V/∂σ²_a = ZAZ'` and
+/// `∂V/∂σ²_e = I`; the average-information matrix entry is `AIᵢⱼ =
+/// 0.5·y'P(∂V/∂θᵢ)P(∂V/∂θⱼ)Py`; update `θ ← θ + AI⁻¹·score` each iteration
+/// until the relative change falls below `tolerance`. If an update would
+/// push a component negative, it's clamped to a small positive floor and
+/// the kernel falls back to a single EM step (`θ_new = θ·(y'P∂V/∂θPy)/
+/// tr(P∂V/∂θ)`) to stay in the parameter space before resuming AI-REML.
+/// [`RemlMethod::EmReml`] runs that EM step alone every iteration.
+///
+/// `var_additive_init`/`var_residual_init` seed the iteration (an even split
+/// of the phenotypic variance is a conventional choice). Returns the
+/// estimated components, heritability, log-likelihood at convergence, and
+/// iteration count; `converged` is `false` (not an error) if
+/// `max_iterations` was reached without meeting `tolerance`.
+///
+/// # Arguments
+/// * `phenotypes` - Phenotypic observations (n x 1)
+/// * `fixed_effects` - Fixed effects design matrix (n x p)
+/// * `random_effects` - Random effects design matrix (n x q)
+/// * `relationship_matrix` - Relationship matrix `A` (q x q)
+pub fn reml_estimate(
+    phenotypes: &[f64],
+    fixed_effects: &[f64],
+    random_effects: &[f64],
+    relationship_matrix: &[f64],
+    var_additive_init: f64,
+    var_residual_init: f64,
+    method: RemlMethod,
+    max_iterations: usize,
+    tolerance: f64,
+    n: usize,
+    p: usize,
+    q: usize,
+) -> ComputeResult<RemlEstimateResult> {
+    if phenotypes.len() != n {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if fixed_effects.len() != n * p {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if random_effects.len() != n * q {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if relationship_matrix.len() != q * q {
+        return Err(ComputeError::InvalidDimensions);
+    }
+
+    let mut var_a = var_additive_init;
+    let mut var_e = var_residual_init;
+    let mut converged: c_int = 0;
+    let mut iterations: c_int = 0;
+    let mut log_lik: c_double = 0.0;
+
+    let method_code = match method {
+        RemlMethod::AiReml => 0,
+        RemlMethod::EmReml => 1,
+    };
+
+    let status = unsafe {
+        compute_reml(
+            phenotypes.as_ptr(),
+            fixed_effects.as_ptr(),
+            random_effects.as_ptr(),
+            relationship_matrix.as_ptr(),
+            &mut var_a as *mut f64,
+            &mut var_e as *mut f64,
+            method_code,
+            n as c_int,
+            p as c_int,
+            q as c_int,
+            max_iterations as c_int,
+            tolerance,
+            &mut converged as *mut c_int,
+            &mut iterations as *mut c_int,
+            &mut log_lik as *mut c_double,
+        )
+    };
+
+    match status {
+        0 => Ok(RemlEstimateResult {
+            var_additive: var_a,
+            var_residual: var_e,
+            heritability: if var_a + var_e > 0.0 { var_a / (var_a + var_e) } else { 0.0 },
+            converged: converged != 0,
+            iterations,
+            log_likelihood: log_lik,
+        }),
+        -1 => Err(ComputeError::MatrixInversionFailed),
+        code => Err(ComputeError::Unknown(code)),
+    }
+}
+
+/// Bivariate REML result: 2x2 genetic and residual covariance matrices
+/// (row-major `[var_1, cov, cov, var_2]`) plus the derived genetic
+/// correlation.
+#[derive(Debug, Clone)]
+pub struct BivarRemlResult {
+    pub genetic_cov: [f64; 4],
+    pub residual_cov: [f64; 4],
+    /// `genetic_cov[1] / sqrt(genetic_cov[0] * genetic_cov[3])`.
+    pub genetic_correlation: f64,
+    pub converged: bool,
+    pub iterations: i32,
+    pub log_likelihood: f64,
+}
+
+/// Bivariate AI-REML: jointly analyzes two phenotype vectors over the same
+/// relationship matrix to estimate the genetic correlation between them.
+///
+/// Extends [`reml_estimate`]'s AI-REML recurrence to parameter vector `θ =
+/// (var_g1, cov_g, var_g2, var_e1, cov_e, var_e2)`: the genetic variance
+/// structure becomes `G⊗A` and the residual structure `R⊗I`, where `G` and
+/// `R` are the 2x2 symmetric genetic/residual covariance matrices; `∂V/∂θᵢ`
+/// are the corresponding Kronecker derivatives, and the same score/
+/// average-information/EM-fallback update from [`reml_estimate`] applies
+/// per component.
+///
+/// `constrain_residual_cov` zeroes `cov_e` throughout (appropriate when the
+/// two traits are measured on disjoint sets of individuals, so there's no
+/// shared-environment residual covariance to estimate).
+/// `fixed_genetic_correlation`, if supplied, holds `r_g` fixed at that value
+/// instead of estimating it — re-run once unconstrained and once fixed to
+/// get the two log-likelihoods a likelihood-ratio test needs.
+///
+/// `genetic_cov_init`/`residual_cov_init` are row-major 2x2 starting values.
+pub fn bivar_reml(
+    phenotype1: &[f64],
+    phenotype2: &[f64],
+    fixed_effects: &[f64],
+    random_effects: &[f64],
+    relationship_matrix: &[f64],
+    genetic_cov_init: [f64; 4],
+    residual_cov_init: [f64; 4],
+    constrain_residual_cov: bool,
+    fixed_genetic_correlation: Option<f64>,
+    n: usize,
+    p: usize,
+    q: usize,
+    max_iterations: usize,
+    tolerance: f64,
+) -> ComputeResult<BivarRemlResult> {
+    if phenotype1.len() != n || phenotype2.len() != n {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if fixed_effects.len() != n * p {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if random_effects.len() != n * q {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if relationship_matrix.len() != q * q {
+        return Err(ComputeError::InvalidDimensions);
+    }
+
+    let mut genetic_cov = genetic_cov_init;
+    let mut residual_cov = residual_cov_init;
+    let mut converged: c_int = 0;
+    let mut iterations: c_int = 0;
+    let mut log_lik: c_double = 0.0;
+
+    let status = unsafe {
+        compute_bivar_reml(
+            phenotype1.as_ptr(),
+            phenotype2.as_ptr(),
+            fixed_effects.as_ptr(),
+            random_effects.as_ptr(),
+            relationship_matrix.as_ptr(),
+            genetic_cov.as_mut_ptr(),
+            residual_cov.as_mut_ptr(),
+            constrain_residual_cov as c_int,
+            fixed_genetic_correlation.is_some() as c_int,
+            fixed_genetic_correlation.unwrap_or(0.0),
+            n as c_int,
+            p as c_int,
+            q as c_int,
+            max_iterations as c_int,
+            tolerance,
+            &mut converged as *mut c_int,
+            &mut iterations as *mut c_int,
+            &mut log_lik as *mut c_double,
+        )
+    };
+
+    match status {
+        0 => {
+            let denom = (genetic_cov[0] * genetic_cov[3]).sqrt();
+            Ok(BivarRemlResult {
+                genetic_cov,
+                residual_cov,
+                genetic_correlation: if denom > 0.0 { genetic_cov[1] / denom } else { 0.0 },
+                converged: converged != 0,
+                iterations,
+                log_likelihood: log_lik,
+            })
+        }
+        -1 => Err(ComputeError::MatrixInversionFailed),
+        code => Err(ComputeError::Unknown(code)),
+    }
+}
+
 /// Genomic Relationship Matrix computation methods
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GrmMethod {
@@ -344,8 +755,57 @@ pub fn solve_mme_pcg(
     tolerance: f64,
     max_iterations: usize,
 ) -> ComputeResult<(Vec<f64>, usize)> {
+    let (solution, iterations, _residual_norm) = solve_mme_with(
+        coefficient_matrix,
+        rhs,
+        initial_guess,
+        SolverMethod::Pcg,
+        tolerance,
+        max_iterations,
+    )?;
+    Ok((solution, iterations))
+}
+
+/// Iterative solver to use for [`solve_mme_with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolverMethod {
+    /// Preconditioned Conjugate Gradient (requires a symmetric
+    /// positive-definite coefficient matrix).
+    Pcg,
+    /// Stabilized Bi-Conjugate Gradient (handles non-symmetric systems).
+    BiCgStab,
+    /// Restarted GMRES with the given Krylov subspace size.
+    Gmres { restart: usize },
+}
+
+/// Solve Mixed Model Equations with a pluggable iterative solver.
+///
+/// `solve_mme_pcg` requires a symmetric positive-definite coefficient
+/// matrix; use [`SolverMethod::BiCgStab`] or [`SolverMethod::Gmres`] for
+/// systems that become non-symmetric after certain fixed-effect
+/// absorptions, or that stall under plain CG.
+///
+/// # Arguments
+/// * `coefficient_matrix` - Coefficient matrix (dim x dim)
+/// * `rhs` - Right-hand side vector (dim x 1)
+/// * `initial_guess` - Initial solution guess (dim x 1)
+/// * `method` - Which iterative solver to dispatch to
+/// * `tolerance` - Convergence tolerance
+/// * `max_iterations` - Maximum number of iterations
+///
+/// # Returns
+/// * Solution vector, number of iterations, and the final residual norm
+///   `‖rhs − coefficient_matrix·solution‖₂`
+pub fn solve_mme_with(
+    coefficient_matrix: &[f64],
+    rhs: &[f64],
+    initial_guess: &[f64],
+    method: SolverMethod,
+    tolerance: f64,
+    max_iterations: usize,
+) -> ComputeResult<(Vec<f64>, usize, f64)> {
     let dim = rhs.len();
-    
+
     if coefficient_matrix.len() != dim * dim {
         return Err(ComputeError::InvalidDimensions);
     }
@@ -356,14 +816,33 @@ pub fn solve_mme_pcg(
     let mut solution = initial_guess.to_vec();
 
     let iterations = unsafe {
-        solve_mme(
-            coefficient_matrix.as_ptr(),
-            rhs.as_ptr(),
-            solution.as_mut_ptr(),
-            dim as c_int,
-            tolerance,
-            max_iterations as c_int,
-        )
+        match method {
+            SolverMethod::Pcg => solve_mme(
+                coefficient_matrix.as_ptr(),
+                rhs.as_ptr(),
+                solution.as_mut_ptr(),
+                dim as c_int,
+                tolerance,
+                max_iterations as c_int,
+            ),
+            SolverMethod::BiCgStab => solve_mme_bicgstab(
+                coefficient_matrix.as_ptr(),
+                rhs.as_ptr(),
+                solution.as_mut_ptr(),
+                dim as c_int,
+                tolerance,
+                max_iterations as c_int,
+            ),
+            SolverMethod::Gmres { restart } => solve_mme_gmres(
+                coefficient_matrix.as_ptr(),
+                rhs.as_ptr(),
+                solution.as_mut_ptr(),
+                dim as c_int,
+                restart as c_int,
+                tolerance,
+                max_iterations as c_int,
+            ),
+        }
     };
 
     if iterations < 0 {
@@ -375,7 +854,339 @@ pub fn solve_mme_pcg(
         });
     }
 
-    Ok((solution, iterations as usize))
+    let mut residual_sq = 0.0;
+    for i in 0..dim {
+        let mut ax_i = 0.0;
+        for j in 0..dim {
+            ax_i += coefficient_matrix[i * dim + j] * solution[j];
+        }
+        let r = rhs[i] - ax_i;
+        residual_sq += r * r;
+    }
+
+    Ok((solution, iterations as usize, residual_sq.sqrt()))
+}
+
+/// Preconditioner for [`solve_mme_sparse`]'s PCG loop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SparsePreconditioner {
+    /// No preconditioning.
+    None,
+    /// Diagonal (Jacobi) scaling.
+    Jacobi,
+    /// Zero-fill incomplete Cholesky (IC(0)): a Cholesky factorization that
+    /// keeps only the nonzero pattern `coefficient_matrix` already has.
+    IncompleteCholesky,
+}
+
+/// Diagonal of a CSR matrix, for Jacobi preconditioning (missing/zero
+/// diagonal entries fall back to `1.0` so the preconditioner stays
+/// applicable).
+fn jacobi_diagonal(matrix: &SparseMatrix) -> Vec<f64> {
+    let mut diag = vec![1.0; matrix.n_rows];
+    for i in 0..matrix.n_rows {
+        for k in matrix.row_ptr[i]..matrix.row_ptr[i + 1] {
+            if matrix.col_indices[k] == i && matrix.values[k].abs() > 1e-12 {
+                diag[i] = matrix.values[k];
+            }
+        }
+    }
+    diag
+}
+
+/// Zero-fill incomplete Cholesky factor `L` of a symmetric positive-definite
+/// CSR matrix, stored both row-wise (for the forward solve) and column-wise
+/// (for the backward solve), keeping only entries `coefficient_matrix`
+/// already has (no fill-in). Returns `None` if a diagonal pivot is
+/// non-positive (the matrix isn't positive-definite on this sparsity
+/// pattern).
+struct IncompleteCholeskyFactor {
+    by_row: Vec<Vec<(usize, f64)>>,
+    by_col: Vec<Vec<(usize, f64)>>,
+}
+
+fn build_incomplete_cholesky(matrix: &SparseMatrix) -> Option<IncompleteCholeskyFactor> {
+    let n = matrix.n_rows;
+    let mut by_row: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    let mut by_col: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+
+    for i in 0..n {
+        for k in matrix.row_ptr[i]..matrix.row_ptr[i + 1] {
+            let j = matrix.col_indices[k];
+            if j > i {
+                continue;
+            }
+            let a_ij = matrix.values[k];
+            if j == i {
+                let mut sum = a_ij;
+                for &(_, l_ik) in &by_row[i] {
+                    sum -= l_ik * l_ik;
+                }
+                if sum <= 0.0 {
+                    return None;
+                }
+                let l_ii = sum.sqrt();
+                by_row[i].push((i, l_ii));
+                by_col[i].push((i, l_ii));
+            } else {
+                let mut sum = a_ij;
+                let (mut pi, mut pj) = (0, 0);
+                while pi < by_row[i].len() && pj < by_row[j].len() {
+                    let ci = by_row[i][pi].0;
+                    let cj = by_row[j][pj].0;
+                    if ci < cj {
+                        pi += 1;
+                    } else if ci > cj {
+                        pj += 1;
+                    } else {
+                        sum -= by_row[i][pi].1 * by_row[j][pj].1;
+                        pi += 1;
+                        pj += 1;
+                    }
+                }
+                let l_jj = by_row[j].last()?.1;
+                let l_ij = sum / l_jj;
+                by_row[i].push((j, l_ij));
+                by_col[j].push((i, l_ij));
+            }
+        }
+    }
+
+    Some(IncompleteCholeskyFactor { by_row, by_col })
+}
+
+/// Apply the IC(0) preconditioner: solve `L·Lᵀ·z = r` via forward then
+/// backward substitution.
+fn apply_incomplete_cholesky(factor: &IncompleteCholeskyFactor, r: &[f64]) -> Vec<f64> {
+    let n = factor.by_row.len();
+
+    let mut y = vec![0.0; n];
+    for i in 0..n {
+        let mut sum = r[i];
+        let mut diag = 1.0;
+        for &(j, v) in &factor.by_row[i] {
+            if j == i {
+                diag = v;
+            } else {
+                sum -= v * y[j];
+            }
+        }
+        y[i] = sum / diag;
+    }
+
+    let mut z = vec![0.0; n];
+    for i in (0..n).rev() {
+        let mut sum = y[i];
+        let mut diag = 1.0;
+        for &(k, v) in &factor.by_col[i] {
+            if k == i {
+                diag = v;
+            } else {
+                sum -= v * z[k];
+            }
+        }
+        z[i] = sum / diag;
+    }
+
+    z
+}
+
+/// Solve Mixed Model Equations with a sparse (CSR) coefficient matrix via
+/// preconditioned conjugate gradient, so pedigrees with millions of animals
+/// (where the coefficient matrix and the pedigree A-inverse feeding it are
+/// extremely sparse) never need a dense `dim x dim` array materialized. The
+/// PCG loop only needs matrix-vector products, so it runs entirely on the
+/// [`SparseMatrix`] form via its own [`SparseMatrix::spmv`] — a trivial,
+/// already-solved-in-pure-Rust primitive with no need for a native kernel.
+///
+/// # Arguments
+/// * `coefficient_matrix` - Sparse coefficient matrix (dim x dim)
+/// * `rhs` - Right-hand side vector (dim x 1)
+/// * `initial_guess` - Initial solution guess (dim x 1)
+/// * `preconditioner` - Jacobi or incomplete-Cholesky preconditioning
+/// * `tolerance` - Convergence tolerance (on the relative residual norm)
+/// * `max_iterations` - Maximum number of iterations
+///
+/// # Returns
+/// * Solution vector and number of iterations
+pub fn solve_mme_sparse(
+    coefficient_matrix: &SparseMatrix,
+    rhs: &[f64],
+    initial_guess: &[f64],
+    preconditioner: SparsePreconditioner,
+    tolerance: f64,
+    max_iterations: usize,
+) -> ComputeResult<(Vec<f64>, usize)> {
+    let dim = rhs.len();
+    if coefficient_matrix.n_rows != dim || coefficient_matrix.n_cols != dim {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if initial_guess.len() != dim {
+        return Err(ComputeError::InvalidDimensions);
+    }
+
+    let jacobi_diag = match preconditioner {
+        SparsePreconditioner::Jacobi => Some(jacobi_diagonal(coefficient_matrix)),
+        _ => None,
+    };
+    let ic0 = match preconditioner {
+        SparsePreconditioner::IncompleteCholesky => Some(
+            build_incomplete_cholesky(coefficient_matrix)
+                .ok_or(ComputeError::MatrixInversionFailed)?,
+        ),
+        _ => None,
+    };
+
+    let apply_preconditioner = |r: &[f64]| -> Vec<f64> {
+        if let Some(diag) = &jacobi_diag {
+            r.iter().zip(diag).map(|(&ri, &di)| ri / di).collect()
+        } else if let Some(factor) = &ic0 {
+            apply_incomplete_cholesky(factor, r)
+        } else {
+            r.to_vec()
+        }
+    };
+
+    let mut x = initial_guess.to_vec();
+    let ax0 = coefficient_matrix.spmv(&x);
+    let mut r: Vec<f64> = rhs.iter().zip(&ax0).map(|(&b, &a)| b - a).collect();
+    let mut z = apply_preconditioner(&r);
+    let mut p = z.clone();
+    let mut rz_old: f64 = r.iter().zip(&z).map(|(&ri, &zi)| ri * zi).sum();
+
+    let rhs_norm = rhs.iter().map(|v| v * v).sum::<f64>().sqrt().max(1e-12);
+
+    for iter in 0..max_iterations {
+        let r_norm = r.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if r_norm / rhs_norm < tolerance {
+            return Ok((x, iter));
+        }
+
+        let ap = coefficient_matrix.spmv(&p);
+        let p_ap: f64 = p.iter().zip(&ap).map(|(&pi, &api)| pi * api).sum();
+        if p_ap.abs() < 1e-300 {
+            return Err(ComputeError::SolveFailure);
+        }
+        let alpha = rz_old / p_ap;
+
+        for i in 0..dim {
+            x[i] += alpha * p[i];
+            r[i] -= alpha * ap[i];
+        }
+
+        z = apply_preconditioner(&r);
+        let rz_new: f64 = r.iter().zip(&z).map(|(&ri, &zi)| ri * zi).sum();
+        let beta = rz_new / rz_old;
+        for i in 0..dim {
+            p[i] = z[i] + beta * p[i];
+        }
+        rz_old = rz_new;
+    }
+
+    let final_norm = r.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if final_norm / rhs_norm < tolerance {
+        Ok((x, max_iterations))
+    } else {
+        Err(ComputeError::ConvergenceFailure {
+            iterations: max_iterations as i32,
+        })
+    }
+}
+
+/// Single-step GBLUP (ssGBLUP) for a partially genotyped cohort.
+///
+/// Blends the pedigree relationship matrix `pedigree_a` (`n_individuals x
+/// n_individuals`) with the genomic relationship matrix `grm`
+/// (`n_genotyped x n_genotyped`, one row/column per entry of
+/// `genotyped_idx`) into the single-step inverse `H⁻¹` via
+/// [`crate::matrix::build_h_inverse`] (see that function for the blending
+/// formula), then assembles the usual `(I + λH⁻¹)` breeding-value
+/// coefficient matrix and solves it with [`solve_mme_pcg`] instead of the
+/// Gauss-Seidel iteration used by the wasm prototype — ungenotyped animals
+/// still get a GEBV because `H⁻¹` pulls them in through the pedigree block
+/// of `A⁻¹`.
+///
+/// # Arguments
+/// * `phenotypes` - Phenotypic observations (n x 1)
+/// * `pedigree_a` - Pedigree relationship matrix (n x n)
+/// * `grm` - Genomic relationship matrix for genotyped animals (g x g)
+/// * `genotyped_idx` - Index of each genotyped animal into `pedigree_a`/`phenotypes`
+/// * `var_additive` - Additive genetic variance
+/// * `var_residual` - Residual variance
+/// * `blend_weight` - Weight given to `grm` when blending toward `A22` (0-1)
+/// * `n_individuals` - Total number of animals, genotyped or not
+///
+/// # Returns
+/// * `BlupResult` with a GEBV (`breeding_values`) for every animal
+pub fn ss_gblup(
+    phenotypes: &[f64],
+    pedigree_a: &[f64],
+    grm: &[f64],
+    genotyped_idx: &[usize],
+    var_additive: f64,
+    var_residual: f64,
+    blend_weight: f64,
+    n_individuals: usize,
+    max_iterations: usize,
+    tolerance: f64,
+) -> ComputeResult<BlupResult> {
+    let n_genotyped = genotyped_idx.len();
+
+    if phenotypes.len() != n_individuals {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if pedigree_a.len() != n_individuals * n_individuals {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if grm.len() != n_genotyped * n_genotyped {
+        return Err(ComputeError::InvalidDimensions);
+    }
+    if genotyped_idx.iter().any(|&i| i >= n_individuals) {
+        return Err(ComputeError::InvalidDimensions);
+    }
+
+    let h_inv = crate::matrix::build_h_inverse(
+        pedigree_a,
+        grm,
+        genotyped_idx,
+        n_individuals,
+        blend_weight,
+    )
+    .ok_or(ComputeError::MatrixInversionFailed)?;
+
+    let lambda = var_residual / var_additive;
+
+    let mut coef = vec![0.0; n_individuals * n_individuals];
+    for i in 0..n_individuals {
+        for j in 0..n_individuals {
+            coef[i * n_individuals + j] = lambda * h_inv[i * n_individuals + j];
+        }
+        coef[i * n_individuals + i] += 1.0;
+    }
+
+    let mut sum = 0.0;
+    let mut count = 0;
+    for &p in phenotypes {
+        if !p.is_nan() {
+            sum += p;
+            count += 1;
+        }
+    }
+    let mean = if count > 0 { sum / count as f64 } else { 0.0 };
+
+    let rhs: Vec<f64> = phenotypes
+        .iter()
+        .map(|&p| if p.is_nan() { 0.0 } else { p - mean })
+        .collect();
+
+    let initial_guess = vec![0.0; n_individuals];
+    let (breeding_values, _iterations) =
+        solve_mme_pcg(&coef, &rhs, &initial_guess, tolerance, max_iterations)?;
+
+    Ok(BlupResult {
+        beta: vec![mean],
+        breeding_values,
+    })
 }
 
 #[cfg(test)]
@@ -401,4 +1212,243 @@ mod tests {
         let result = compute_grm(&genotypes, GrmMethod::VanRaden1, 10, 100);
         assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
     }
+
+    // `reml_estimate` and `bivar_reml` below only check dimensions before
+    // handing off to `compute_reml`/`compute_bivar_reml`, which are linked
+    // in from `mock_fortran.c` (see `build.rs`) — a file this checkout does
+    // not carry. There's no way to assert a worked-example numeric result
+    // for either function without that kernel's actual AI-REML/EM-REML
+    // iteration present to run, so dimension validation is the only
+    // coverage we can give them here.
+
+    #[test]
+    fn test_reml_estimate_invalid_dimensions() {
+        let phenotypes = vec![0.0; 10];
+        let fixed_effects = vec![0.0; 10]; // Wrong size for p=2
+        let random_effects = vec![0.0; 10 * 10];
+        let relationship_matrix = vec![0.0; 10 * 10];
+
+        let result = reml_estimate(
+            &phenotypes,
+            &fixed_effects,
+            &random_effects,
+            &relationship_matrix,
+            0.5,
+            0.5,
+            RemlMethod::AiReml,
+            50,
+            1e-4,
+            10,
+            2,
+            10,
+        );
+        assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
+    }
+
+    // Same limitation as `reml_estimate` above: `bivar_reml` only validates
+    // dimensions natively before calling into `compute_bivar_reml`, and the
+    // bivariate AI-REML Kronecker-derivative math it implements isn't
+    // reproducible as a closed-form assertion without that external kernel.
+
+    #[test]
+    fn test_bivar_reml_invalid_dimensions() {
+        let phenotype1 = vec![0.0; 10];
+        let phenotype2 = vec![0.0; 8]; // Wrong size
+        let fixed_effects = vec![0.0; 10 * 2];
+        let random_effects = vec![0.0; 10 * 10];
+        let relationship_matrix = vec![0.0; 10 * 10];
+
+        let result = bivar_reml(
+            &phenotype1,
+            &phenotype2,
+            &fixed_effects,
+            &random_effects,
+            &relationship_matrix,
+            [1.0, 0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0],
+            false,
+            None,
+            10,
+            2,
+            10,
+            50,
+            1e-4,
+        );
+        assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
+    }
+
+    #[test]
+    fn test_ss_gblup_invalid_dimensions() {
+        let phenotypes = vec![0.0; 10];
+        let pedigree_a = vec![0.0; 10 * 10];
+        let grm = vec![0.0; 4 * 4]; // Wrong size for 5 genotyped animals
+        let genotyped_idx = vec![0, 1, 2, 3, 4];
+
+        let result = ss_gblup(
+            &phenotypes,
+            &pedigree_a,
+            &grm,
+            &genotyped_idx,
+            0.5,
+            0.5,
+            0.95,
+            10,
+            100,
+            1e-6,
+        );
+        assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
+    }
+
+    // `ss_gblup` builds its `H⁻¹`-blended coefficient matrix natively, but
+    // the actual solve goes through `solve_mme_pcg` -> `solve_mme_with` ->
+    // the extern `solve_mme` kernel, which — like `compute_reml` above —
+    // is linked in from `mock_fortran.c`, a file this checkout does not
+    // carry. A worked-example assertion here would depend on a solver we
+    // can't run, so dimension validation is the only coverage we can give
+    // it.
+
+    #[test]
+    fn test_solve_mme_with_invalid_dimensions() {
+        let coefficient_matrix = vec![0.0; 5 * 5]; // Wrong size for dim=10
+        let rhs = vec![0.0; 10];
+        let initial_guess = vec![0.0; 10];
+
+        let result = solve_mme_with(
+            &coefficient_matrix,
+            &rhs,
+            &initial_guess,
+            SolverMethod::BiCgStab,
+            1e-6,
+            100,
+        );
+        assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
+
+        let result = solve_mme_with(
+            &coefficient_matrix,
+            &rhs,
+            &initial_guess,
+            SolverMethod::Gmres { restart: 5 },
+            1e-6,
+            100,
+        );
+        assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
+    }
+
+    // `solve_mme_with` dispatches every `SolverMethod` straight to an
+    // extern kernel (`solve_mme`/`solve_mme_bicgstab`/`solve_mme_gmres`),
+    // all linked in from the same missing `mock_fortran.c` as
+    // `compute_reml` above, so a worked-example assertion on its solved
+    // output can't actually run in this checkout; dimension validation is
+    // the only coverage available here.
+
+    #[test]
+    fn test_blup_sparse_invalid_dimensions() {
+        let phenotypes = vec![0.0; 10];
+        let fixed_effects = vec![0.0; 10 * 2];
+        let random_effects = vec![0.0; 10 * 10];
+        let a_inverse = SparseMatrix {
+            values: vec![1.0; 5],
+            col_indices: vec![0, 1, 2, 3, 4],
+            row_ptr: vec![0, 1, 2, 3, 4, 5],
+            n_rows: 5, // Wrong size for q=10
+            n_cols: 5,
+        };
+
+        let result = blup_sparse(
+            &phenotypes,
+            &fixed_effects,
+            &random_effects,
+            &a_inverse,
+            0.5,
+            0.5,
+            10,
+            2,
+            10,
+        );
+        assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
+    }
+
+    #[test]
+    fn test_solve_mme_sparse_invalid_dimensions() {
+        let coefficient_matrix = SparseMatrix {
+            values: vec![1.0; 5],
+            col_indices: vec![0, 1, 2, 3, 4],
+            row_ptr: vec![0, 1, 2, 3, 4, 5],
+            n_rows: 5, // Wrong size for dim=10
+            n_cols: 5,
+        };
+        let rhs = vec![0.0; 10];
+        let initial_guess = vec![0.0; 10];
+
+        let result = solve_mme_sparse(
+            &coefficient_matrix,
+            &rhs,
+            &initial_guess,
+            SparsePreconditioner::Jacobi,
+            1e-6,
+            100,
+        );
+        assert!(matches!(result, Err(ComputeError::InvalidDimensions)));
+    }
+
+    // `blup_sparse` only validates dimensions natively before calling into
+    // `compute_blup_sparse`, which — like `compute_reml`/`compute_bivar_reml`
+    // above — is linked in from `mock_fortran.c` (see `build.rs`), a file
+    // this checkout does not carry. So, as with `reml_estimate`/
+    // `bivar_reml`, dimension validation is the only coverage we can give
+    // it here; unlike `solve_mme_sparse` below, its solve path has no
+    // pure-Rust fallback to test against instead.
+
+    #[test]
+    fn test_solve_mme_sparse_matches_the_dense_tridiagonal_solution() {
+        // Symmetric, diagonally-dominant tridiagonal coefficient matrix
+        // [[4,1,0],[1,4,1],[0,1,4]] stored as CSR; rhs chosen so the exact
+        // solution is the round-trippable x = [1.0, 2.0, 1.0]:
+        //   4*1 + 1*2 + 0*1 =  6
+        //   1*1 + 4*2 + 1*1 = 10
+        //   0*1 + 1*2 + 4*1 =  6
+        // Unlike `blup_sparse` above, `solve_mme_sparse`'s PCG loop runs
+        // entirely on `SparseMatrix::spmv` (pure Rust, no FFI), so this
+        // worked example actually exercises the real solve path.
+        let coefficient_matrix = SparseMatrix {
+            values: vec![4.0, 1.0, 1.0, 4.0, 1.0, 1.0, 4.0],
+            col_indices: vec![0, 1, 0, 1, 2, 1, 2],
+            row_ptr: vec![0, 2, 5, 7],
+            n_rows: 3,
+            n_cols: 3,
+        };
+        let rhs = vec![6.0, 10.0, 6.0];
+        let initial_guess = vec![0.0, 0.0, 0.0];
+
+        let (solution, _iterations) = solve_mme_sparse(
+            &coefficient_matrix,
+            &rhs,
+            &initial_guess,
+            SparsePreconditioner::Jacobi,
+            1e-10,
+            100,
+        )
+        .expect("diagonally-dominant system converges");
+
+        assert!((solution[0] - 1.0).abs() < 1e-6);
+        assert!((solution[1] - 2.0).abs() < 1e-6);
+        assert!((solution[2] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_build_a_inverse_sparse_diagonal_for_unrelated_founders() {
+        // No parents known for any animal: A⁻¹ should be the identity.
+        let sire_ids = vec![-1, -1, -1];
+        let dam_ids = vec![-1, -1, -1];
+
+        let a_inv = crate::matrix::build_a_inverse_sparse(&sire_ids, &dam_ids);
+        assert_eq!(a_inv.n_rows, 3);
+        assert_eq!(a_inv.nnz(), 3);
+        for i in 0..3 {
+            for k in a_inv.row_ptr[i]..a_inv.row_ptr[i + 1] {
+                assert_eq!(a_inv.col_indices[k], i);
+                assert!((a_inv.values[k] - 1.0).abs() < 1e-9);
+            }
+        }
+    }
 }
@@ -72,6 +72,90 @@ pub fn calculate_allele_frequencies(genotypes: &[i32], n_samples: usize, n_marke
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
+/// Result of EM-based allele-frequency estimation from genotype likelihoods
+#[derive(Serialize, Deserialize)]
+pub struct AlleleFrequenciesGL {
+    pub allele_freq: Vec<f64>,
+    pub dosages: Vec<f64>,
+    pub iterations: Vec<u32>,
+}
+
+/// Estimate allele frequencies and per-sample expected dosage via EM from
+/// genotype likelihoods, instead of assuming hard 0/1/2 calls.
+///
+/// `likelihoods` is laid out `[sample][marker][AA, AB, BB]`, i.e. length
+/// `n_samples * n_markers * 3`. `allele_freq` is the MLE frequency of the
+/// allele counted by `AB`/`AA` (one copy / two copies). `dosages` is the
+/// expected genotype `E[g] = post_AB + 2*post_AA` per sample/marker, a
+/// soft-call matrix that downstream GRM/LD code can consume in place of
+/// hard calls.
+#[wasm_bindgen]
+pub fn calculate_allele_frequencies_gl(likelihoods: &[f64], n_samples: usize, n_markers: usize) -> JsValue {
+    let mut allele_freq = vec![0.0; n_markers];
+    let mut dosages = vec![0.0; n_samples * n_markers];
+    let mut iterations = vec![0u32; n_markers];
+
+    for j in 0..n_markers {
+        // Naive initialization: mean dosage under a flat genotype prior.
+        let mut f = {
+            let mut sum = 0.0;
+            for i in 0..n_samples {
+                let base = (i * n_markers + j) * 3;
+                let (l_aa, l_ab, l_bb) = (likelihoods[base], likelihoods[base + 1], likelihoods[base + 2]);
+                let total = l_aa + l_ab + l_bb;
+                if total > 0.0 {
+                    sum += (l_ab + 2.0 * l_aa) / total;
+                }
+            }
+            sum / (2.0 * n_samples as f64)
+        };
+
+        let mut post_aa = vec![0.0; n_samples];
+        let mut post_ab = vec![0.0; n_samples];
+        let mut iters = 0u32;
+
+        for _ in 0..50 {
+            iters += 1;
+            let prior_aa = f * f;
+            let prior_ab = 2.0 * f * (1.0 - f);
+            let prior_bb = (1.0 - f) * (1.0 - f);
+
+            let mut f_sum = 0.0;
+            for i in 0..n_samples {
+                let base = (i * n_markers + j) * 3;
+                let (l_aa, l_ab, l_bb) = (likelihoods[base], likelihoods[base + 1], likelihoods[base + 2]);
+
+                let u_aa = prior_aa * l_aa;
+                let u_ab = prior_ab * l_ab;
+                let u_bb = prior_bb * l_bb;
+                let total = u_aa + u_ab + u_bb;
+
+                let (p_aa, p_ab) = if total > 0.0 { (u_aa / total, u_ab / total) } else { (0.0, 0.0) };
+                post_aa[i] = p_aa;
+                post_ab[i] = p_ab;
+
+                f_sum += p_ab + 2.0 * p_aa;
+            }
+
+            let f_new = f_sum / (2.0 * n_samples as f64);
+            let delta = (f_new - f).abs();
+            f = f_new;
+            if delta < 1e-5 {
+                break;
+            }
+        }
+
+        allele_freq[j] = f;
+        iterations[j] = iters;
+        for i in 0..n_samples {
+            dosages[i * n_markers + j] = post_ab[i] + 2.0 * post_aa[i];
+        }
+    }
+
+    let result = AlleleFrequenciesGL { allele_freq, dosages, iterations };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 /// LD (Linkage Disequilibrium) result
 #[derive(Serialize, Deserialize)]
 pub struct LDResult {
@@ -133,10 +217,13 @@ pub fn calculate_ld_pair(geno1: &[i32], geno2: &[i32]) -> JsValue {
         0.0
     };
 
+    // LD significance test: chi-squared = n * r^2, 1 df
+    let p_value = crate::stats::chi_square_sf(n_f * r_squared, 1.0);
+
     let result = LDResult {
         r_squared,
         d_prime: d_prime.min(1.0),
-        p_value: 0.0, // Would need chi-square calculation
+        p_value,
     };
 
     serde_wasm_bindgen::to_value(&result).unwrap()
@@ -207,6 +294,237 @@ pub fn calculate_ld_matrix(genotypes: &[i32], n_samples: usize, n_markers: usize
     ld_matrix
 }
 
+/// Result of EM-phased two-locus LD estimation
+#[derive(Serialize, Deserialize)]
+pub struct LDResultEM {
+    /// Haplotype frequencies `[p_AB, p_Ab, p_aB, p_ab]`, where `A`/`B` are
+    /// the counted alleles at locus 1/locus 2.
+    pub haplotype_freqs: Vec<f64>,
+    pub d: f64,
+    pub d_prime: f64,
+    pub r_squared: f64,
+    pub p_value: f64,
+    pub iterations: u32,
+}
+
+/// Two-locus LD via EM haplotype phasing, giving the real Lewontin D' (not
+/// just a normalized correlation) and a phase-aware r-squared/p-value.
+///
+/// `geno1`/`geno2` are per-locus dosages (0/1/2 copies of the counted
+/// allele; missing is negative). Unambiguous two-locus genotype classes are
+/// counted directly; the only ambiguous class, the double heterozygote, is
+/// split between the coupling (AB/ab) and repulsion (Ab/aB) configurations
+/// in proportion to the current haplotype-frequency products. Haplotype
+/// frequencies are re-estimated each iteration and the process repeats to
+/// convergence (`|Δ| < 1e-6` or 100 iterations).
+#[wasm_bindgen]
+pub fn calculate_ld_pair_em(geno1: &[i32], geno2: &[i32]) -> JsValue {
+    let (haplotype_freqs, d, d_prime, r_squared, p_value, iterations) = ld_pair_em_core(geno1, geno2);
+
+    let result = LDResultEM {
+        haplotype_freqs,
+        d,
+        d_prime,
+        r_squared,
+        p_value,
+        iterations,
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+/// Core two-locus EM haplotype-phasing computation shared by
+/// [`calculate_ld_pair_em`] and [`calculate_ld`]. Returns
+/// `(haplotype_freqs [p_AB, p_Ab, p_aB, p_ab], d, d_prime, r_squared,
+/// p_value, iterations)`.
+fn ld_pair_em_core(geno1: &[i32], geno2: &[i32]) -> (Vec<f64>, f64, f64, f64, f64, u32) {
+    // counts[g1][g2] tallies the 9 two-locus genotype classes.
+    let mut counts = [[0.0_f64; 3]; 3];
+    let mut n_valid = 0.0;
+
+    for i in 0..geno1.len().min(geno2.len()) {
+        let a = geno1[i];
+        let b = geno2[i];
+        if !(0..=2).contains(&a) || !(0..=2).contains(&b) {
+            continue;
+        }
+        counts[a as usize][b as usize] += 1.0;
+        n_valid += 1.0;
+    }
+
+    if n_valid < 1.0 {
+        return (vec![0.25, 0.25, 0.25, 0.25], 0.0, 0.0, 0.0, 1.0, 0);
+    }
+
+    let p_a = (2.0 * (counts[2][0] + counts[2][1] + counts[2][2]) + counts[1][0] + counts[1][1] + counts[1][2])
+        / (2.0 * n_valid);
+    let p_b = (2.0 * (counts[0][2] + counts[1][2] + counts[2][2]) + counts[0][1] + counts[1][1] + counts[2][1])
+        / (2.0 * n_valid);
+
+    // Initialize haplotype frequencies at linkage equilibrium.
+    let mut p_ab = p_a * p_b;
+    let mut p_a_b = p_a * (1.0 - p_b);
+    let mut p_ab_ = (1.0 - p_a) * p_b;
+    let mut p_ab_ab = (1.0 - p_a) * (1.0 - p_b);
+
+    let n11 = counts[1][1];
+    let total = 2.0 * n_valid;
+    let mut iterations = 0u32;
+
+    for _ in 0..100 {
+        iterations += 1;
+
+        let denom = p_ab * p_ab_ab + p_a_b * p_ab_;
+        let n_coupling = if denom > 0.0 { n11 * (p_ab * p_ab_ab) / denom } else { 0.0 };
+        let n_repulsion = n11 - n_coupling;
+
+        let new_ab = (2.0 * counts[2][2] + counts[2][1] + counts[1][2] + n_coupling) / total;
+        let new_a_b = (2.0 * counts[2][0] + counts[2][1] + counts[1][0] + n_repulsion) / total;
+        let new_ab_ = (2.0 * counts[0][2] + counts[1][2] + counts[0][1] + n_repulsion) / total;
+        let new_ab_ab = (2.0 * counts[0][0] + counts[1][0] + counts[0][1] + n_coupling) / total;
+
+        let delta = (new_ab - p_ab).abs()
+            + (new_a_b - p_a_b).abs()
+            + (new_ab_ - p_ab_).abs()
+            + (new_ab_ab - p_ab_ab).abs();
+
+        p_ab = new_ab;
+        p_a_b = new_a_b;
+        p_ab_ = new_ab_;
+        p_ab_ab = new_ab_ab;
+
+        if delta < 1e-6 {
+            break;
+        }
+    }
+
+    let d = p_ab - p_a * p_b;
+    let d_max = if d >= 0.0 {
+        (p_a * (1.0 - p_b)).min((1.0 - p_a) * p_b)
+    } else {
+        (p_a * p_b).min((1.0 - p_a) * (1.0 - p_b))
+    };
+    let d_prime = if d_max > 0.0 { (d / d_max).clamp(-1.0, 1.0) } else { 0.0 };
+
+    let var_a = p_a * (1.0 - p_a);
+    let var_b = p_b * (1.0 - p_b);
+    let r_squared = if var_a > 0.0 && var_b > 0.0 { (d * d) / (var_a * var_b) } else { 0.0 };
+
+    let p_value = crate::stats::chi_square_sf(n_valid * r_squared, 1.0);
+
+    (vec![p_ab, p_a_b, p_ab_, p_ab_ab], d, d_prime, r_squared, p_value, iterations)
+}
+
+/// One pairwise LD estimate from [`calculate_ld`].
+#[derive(Serialize, Deserialize)]
+pub struct LDPairStat {
+    pub marker_i: usize,
+    pub marker_j: usize,
+    pub d: f64,
+    pub d_prime: f64,
+    pub r_squared: f64,
+}
+
+/// One bin of the LD-decay curve from [`calculate_ld`].
+#[derive(Serialize, Deserialize)]
+pub struct LDDecayBin {
+    pub distance_lower: f64,
+    pub distance_upper: f64,
+    pub mean_r_squared: f64,
+    pub n_pairs: usize,
+}
+
+/// Genome-wide pairwise LD result
+#[derive(Serialize, Deserialize)]
+pub struct LDGenomeResult {
+    pub pairs: Vec<LDPairStat>,
+    /// LD-decay curve (mean r² per inter-marker distance bin). Empty when
+    /// `positions` wasn't supplied to [`calculate_ld`].
+    pub decay_curve: Vec<LDDecayBin>,
+    pub n_pairs_computed: usize,
+    pub n_pairs_skipped: usize,
+}
+
+/// Genome-wide pairwise LD: for every marker pair (up to `max_pairs`),
+/// phases haplotype frequencies via the same EM step as
+/// [`calculate_ld_pair_em`] and reports `D`, `D'`, and `r²`. Pass an empty
+/// `positions` slice to skip distance binning; otherwise `positions[j]`
+/// gives marker `j`'s physical position and pairs are binned into 10
+/// equal-width bins spanning the observed distance range, each reporting
+/// mean `r²`, to visualize LD decay / haplotype-block structure.
+///
+/// All-pairs cost is `O(m²)`, so once `max_pairs` pairs have been computed
+/// the scan stops early (pairs are visited in `(i, i+1..m)` order, i.e. a
+/// sliding window outward from each marker) — `n_pairs_skipped` reports how
+/// many candidate pairs were never evaluated.
+#[wasm_bindgen]
+pub fn calculate_ld(
+    genotypes: &[i32],
+    n_samples: usize,
+    n_markers: usize,
+    positions: &[f64],
+    max_pairs: usize,
+) -> JsValue {
+    let use_positions = positions.len() == n_markers;
+    let total_pairs = n_markers * n_markers.saturating_sub(1) / 2;
+
+    let mut pairs = Vec::new();
+    let mut distances_r2 = Vec::new();
+    let mut computed = 0usize;
+
+    'outer: for i in 0..n_markers {
+        let geno_i: Vec<i32> = (0..n_samples).map(|s| genotypes[s * n_markers + i]).collect();
+        for j in (i + 1)..n_markers {
+            if computed >= max_pairs {
+                break 'outer;
+            }
+            let geno_j: Vec<i32> = (0..n_samples).map(|s| genotypes[s * n_markers + j]).collect();
+
+            let (_, d, d_prime, r_squared, _, _) = ld_pair_em_core(&geno_i, &geno_j);
+            pairs.push(LDPairStat { marker_i: i, marker_j: j, d, d_prime, r_squared });
+            computed += 1;
+
+            if use_positions {
+                distances_r2.push(((positions[j] - positions[i]).abs(), r_squared));
+            }
+        }
+    }
+
+    let decay_curve = if use_positions && !distances_r2.is_empty() {
+        let max_dist = distances_r2.iter().map(|&(dist, _)| dist).fold(0.0_f64, f64::max);
+        let n_bins = 10;
+        let bin_width = if max_dist > 0.0 { max_dist / n_bins as f64 } else { 1.0 };
+
+        let mut bin_sums = vec![0.0; n_bins];
+        let mut bin_counts = vec![0usize; n_bins];
+        for &(dist, r2) in &distances_r2 {
+            let bin = ((dist / bin_width) as usize).min(n_bins - 1);
+            bin_sums[bin] += r2;
+            bin_counts[bin] += 1;
+        }
+
+        (0..n_bins)
+            .map(|b| LDDecayBin {
+                distance_lower: b as f64 * bin_width,
+                distance_upper: (b + 1) as f64 * bin_width,
+                mean_r_squared: if bin_counts[b] > 0 { bin_sums[b] / bin_counts[b] as f64 } else { 0.0 },
+                n_pairs: bin_counts[b],
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let result = LDGenomeResult {
+        n_pairs_skipped: total_pairs.saturating_sub(computed),
+        n_pairs_computed: computed,
+        pairs,
+        decay_curve,
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 /// Hardy-Weinberg equilibrium test result
 #[derive(Serialize, Deserialize)]
 pub struct HWEResult {
@@ -255,8 +573,8 @@ pub fn test_hwe(genotypes: &[i32]) -> JsValue {
              + if exp_ab > 0.0 { (n_ab as f64 - exp_ab).powi(2) / exp_ab } else { 0.0 }
              + if exp_bb > 0.0 { (n_bb as f64 - exp_bb).powi(2) / exp_bb } else { 0.0 };
 
-    // Approximate p-value using chi-square with 1 df
-    let p_value = (-chi2 / 2.0).exp();
+    // p-value from the chi-square survival function with 1 df
+    let p_value = crate::stats::chi_square_sf(chi2, 1.0);
 
     let result = HWEResult {
         chi_squared: chi2,
@@ -298,6 +616,142 @@ pub fn filter_by_maf(genotypes: &[i32], n_samples: usize, n_markers: usize, min_
     passing_indices
 }
 
+/// Transition matrix for an F2-style 3-state genotype HMM (AA, AB, BB) given
+/// the per-homolog recombination fraction `r`, derived by treating each of
+/// the two homologs as an independent 2-state ancestry chain and summing
+/// over the orderings that give each unordered genotype.
+fn hmm_transition_matrix(r: f64) -> [[f64; 3]; 3] {
+    let s = 1.0 - r;
+    [
+        [s * s, 2.0 * r * s, r * r],
+        [r * s, s * s + r * r, r * s],
+        [r * r, 2.0 * r * s, s * s],
+    ]
+}
+
+/// Result of HMM-based genotype error correction / imputation
+#[derive(Serialize, Deserialize)]
+pub struct HmmCleanResult {
+    pub genotypes: Vec<i32>,
+    /// Row-major `n_samples * n_markers * 3` posterior probabilities over
+    /// the (AA, AB, BB) states.
+    pub posteriors: Vec<f64>,
+}
+
+/// Clean and impute genotypes with a per-individual forward-backward HMM
+/// along ordered markers, instead of filling missing calls with the marker
+/// mean.
+///
+/// Hidden states are the three F2 genotype classes (AA, AB, BB); transition
+/// probabilities between adjacent markers come from the recombination
+/// fraction implied by their cM gap via Haldane's mapping function
+/// `r = 0.5(1 - e^-2d)`, floored at `recomb_rate` so adjacent markers at the
+/// same position (or on separate scaffolds) still allow some state change.
+/// Emission probabilities assign `1 - error_rate` to the observed call
+/// matching the true state and split `error_rate` evenly over the other two
+/// states; missing calls (`< 0`) emit uniformly. Returns the posterior-decoded
+/// (most likely) genotype per sample/marker alongside the full posterior
+/// matrix.
+#[wasm_bindgen]
+pub fn hmm_clean_genotypes(
+    genotypes: &[i32],
+    n_samples: usize,
+    n_markers: usize,
+    positions_cm: &[f64],
+    recomb_rate: f64,
+    error_rate: f64,
+) -> JsValue {
+    const N_STATES: usize = 3;
+    let prior = [0.25, 0.5, 0.25]; // Mendelian F2 expectation: 1:2:1
+
+    let emission = |state: usize, obs: i32| -> f64 {
+        if obs < 0 {
+            1.0 / N_STATES as f64
+        } else if obs as usize == state {
+            1.0 - error_rate
+        } else {
+            error_rate / (N_STATES as f64 - 1.0)
+        }
+    };
+
+    let r_between = |d_cm: f64| -> f64 {
+        let d_morgans = (d_cm / 100.0).abs();
+        let r = 0.5 * (1.0 - (-2.0 * d_morgans).exp());
+        r.max(recomb_rate).min(0.5)
+    };
+
+    let mut decoded = vec![0i32; n_samples * n_markers];
+    let mut posteriors = vec![0.0; n_samples * n_markers * N_STATES];
+
+    for i in 0..n_samples {
+        if n_markers == 0 {
+            continue;
+        }
+        let obs: Vec<i32> = (0..n_markers).map(|j| genotypes[i * n_markers + j]).collect();
+
+        let mut alpha = vec![[0.0f64; N_STATES]; n_markers];
+        let mut scale = vec![0.0; n_markers];
+
+        for s in 0..N_STATES {
+            alpha[0][s] = prior[s] * emission(s, obs[0]);
+        }
+        scale[0] = alpha[0].iter().sum();
+        if scale[0] > 0.0 {
+            for s in 0..N_STATES {
+                alpha[0][s] /= scale[0];
+            }
+        }
+
+        for t in 1..n_markers {
+            let trans = hmm_transition_matrix(r_between(positions_cm[t] - positions_cm[t - 1]));
+            for s in 0..N_STATES {
+                let sum: f64 = (0..N_STATES).map(|sp| alpha[t - 1][sp] * trans[sp][s]).sum();
+                alpha[t][s] = sum * emission(s, obs[t]);
+            }
+            scale[t] = alpha[t].iter().sum();
+            if scale[t] > 0.0 {
+                for s in 0..N_STATES {
+                    alpha[t][s] /= scale[t];
+                }
+            }
+        }
+
+        let mut beta = vec![[1.0f64; N_STATES]; n_markers];
+        for t in (0..n_markers - 1).rev() {
+            let trans = hmm_transition_matrix(r_between(positions_cm[t + 1] - positions_cm[t]));
+            for s in 0..N_STATES {
+                let sum: f64 = (0..N_STATES).map(|sp| trans[s][sp] * emission(sp, obs[t + 1]) * beta[t + 1][sp]).sum();
+                beta[t][s] = if scale[t + 1] > 0.0 { sum / scale[t + 1] } else { sum };
+            }
+        }
+
+        for t in 0..n_markers {
+            let mut post = [0.0; N_STATES];
+            let mut total = 0.0;
+            for s in 0..N_STATES {
+                post[s] = alpha[t][s] * beta[t][s];
+                total += post[s];
+            }
+            if total > 0.0 {
+                for s in 0..N_STATES {
+                    post[s] /= total;
+                }
+            } else {
+                post = [1.0 / N_STATES as f64; N_STATES];
+            }
+
+            let best = (0..N_STATES).max_by(|&a, &b| post[a].partial_cmp(&post[b]).unwrap()).unwrap();
+            decoded[i * n_markers + t] = best as i32;
+            for s in 0..N_STATES {
+                posteriors[(i * n_markers + t) * N_STATES + s] = post[s];
+            }
+        }
+    }
+
+    let result = HmmCleanResult { genotypes: decoded, posteriors };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 /// Impute missing genotypes using mean imputation
 #[wasm_bindgen]
 pub fn impute_missing_mean(genotypes: &[i32], n_samples: usize, n_markers: usize) -> Vec<f64> {
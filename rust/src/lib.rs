@@ -3,6 +3,11 @@ mod genomics;
 mod statistics;
 mod matrix;
 mod population;
+mod stats;
+mod gwas;
+mod breeding_sim;
+mod simulate;
+mod sequence;
 
 // Fortran FFI layer (for native builds, not WASM)
 #[cfg(not(target_arch = "wasm32"))]
@@ -25,6 +30,9 @@ pub use genomics::*;
 pub use statistics::*;
 pub use matrix::*;
 pub use population::*;
+pub use gwas::*;
+pub use breeding_sim::*;
+pub use sequence::*;
 
 /// Get library version
 #[wasm_bindgen]
@@ -48,5 +56,14 @@ fn bijmantra_compute(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(python_bindings::blup, m)?)?;
     m.add_function(wrap_pyfunction!(python_bindings::gblup, m)?)?;
     m.add_function(wrap_pyfunction!(python_bindings::compute_grm, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::reml_estimate, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::bivar_reml, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::ss_gblup, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::solve_mme_with, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::build_a_inverse_sparse, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::blup_sparse, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::solve_mme_sparse, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::simulate_population, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::ndarray_broadcast_add, m)?)?;
     Ok(())
 }
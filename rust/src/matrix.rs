@@ -3,7 +3,7 @@
 
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
-use ndarray::{Array2, Axis};
+use ndarray::{Array2, ArrayD, Axis, IxDyn, Slice, Zip};
 
 /// Genomic Relationship Matrix result
 #[derive(Serialize, Deserialize)]
@@ -106,6 +106,187 @@ pub fn calculate_grm(genotypes: &[i32], n_samples: usize, n_markers: usize) -> J
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
+/// GRM construction method for [`build_grm`].
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrmMethod {
+    /// VanRaden Method 1 (2008): `G = ZZ' / (2·Σpⱼ(1−pⱼ))`, a single
+    /// genome-wide scaling factor (equivalent to [`calculate_grm`]).
+    VanRaden1,
+    /// VanRaden Method 2 (2008): `G = ZDZ' / m`, with `D` a diagonal matrix
+    /// of per-marker weights `1/(2pⱼ(1−pⱼ))` — up-weights rare variants'
+    /// contribution to genome-wide relatedness relative to Method 1.
+    VanRaden2,
+}
+
+/// VanRaden Method 1 relationship matrix: `G = ZZ' / (2·Σpⱼ(1−pⱼ))`, `Z`
+/// the genotype matrix centered on `2pⱼ`. Shared by [`build_grm`] and
+/// [`crate::population::calculate_relatedness`] so the math lives in one
+/// place.
+pub(crate) fn vanraden_grm(markers: &[i32], n_individuals: usize, n_markers: usize) -> Vec<f64> {
+    let mut freqs = vec![0.0; n_markers];
+    for j in 0..n_markers {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for i in 0..n_individuals {
+            let geno = markers[i * n_markers + j];
+            if geno >= 0 {
+                sum += geno as f64;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            freqs[j] = sum / (2.0 * count as f64);
+        }
+    }
+
+    let mut scale: f64 = freqs.iter().filter(|&&p| p > 0.0 && p < 1.0).map(|&p| 2.0 * p * (1.0 - p)).sum();
+    if scale == 0.0 {
+        scale = 1.0;
+    }
+
+    let mut z = vec![0.0; n_individuals * n_markers];
+    for i in 0..n_individuals {
+        for j in 0..n_markers {
+            let idx = i * n_markers + j;
+            let geno = markers[idx];
+            z[idx] = if geno >= 0 { geno as f64 - 2.0 * freqs[j] } else { 0.0 };
+        }
+    }
+
+    let mut grm = vec![0.0; n_individuals * n_individuals];
+    for i in 0..n_individuals {
+        for k in i..n_individuals {
+            let mut sum = 0.0;
+            for j in 0..n_markers {
+                sum += z[i * n_markers + j] * z[k * n_markers + j];
+            }
+            let g_ik = sum / scale;
+            grm[i * n_individuals + k] = g_ik;
+            grm[k * n_individuals + i] = g_ik;
+        }
+    }
+
+    grm
+}
+
+/// Build a Genomic Relationship Matrix directly from a 0/1/2 marker dosage
+/// matrix (row-major `n_individuals * n_markers`, missing calls negative),
+/// so callers don't need to precompute the GRM in JS before calling the
+/// BLUP/GBLUP family. Centers each marker column on `2·pⱼ` (`pⱼ` its allele
+/// frequency) to form `Z = M − 2P`, then combines columns per `method` (see
+/// [`GrmMethod`]). Output layout matches [`calculate_grm`]'s.
+#[wasm_bindgen]
+pub fn build_grm(markers: &[i32], n_individuals: usize, n_markers: usize, method: GrmMethod) -> JsValue {
+    let mut freqs = vec![0.0; n_markers];
+    let mut counts = vec![0usize; n_markers];
+
+    for j in 0..n_markers {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for i in 0..n_individuals {
+            let geno = markers[i * n_markers + j];
+            if geno >= 0 {
+                sum += geno as f64;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            freqs[j] = sum / (2.0 * count as f64);
+        }
+        counts[j] = count;
+    }
+
+    let mut z = vec![0.0; n_individuals * n_markers];
+    for i in 0..n_individuals {
+        for j in 0..n_markers {
+            let idx = i * n_markers + j;
+            let geno = markers[idx];
+            z[idx] = if geno >= 0 { geno as f64 - 2.0 * freqs[j] } else { 0.0 };
+        }
+    }
+
+    let mut markers_used = 0;
+    let mut grm = vec![0.0; n_individuals * n_individuals];
+
+    match method {
+        GrmMethod::VanRaden1 => {
+            for &p in &freqs {
+                if p > 0.0 && p < 1.0 {
+                    markers_used += 1;
+                }
+            }
+            grm = vanraden_grm(markers, n_individuals, n_markers);
+        }
+        GrmMethod::VanRaden2 => {
+            let weights: Vec<f64> = freqs.iter().map(|&p| {
+                if p > 0.0 && p < 1.0 { 1.0 / (2.0 * p * (1.0 - p)) } else { 0.0 }
+            }).collect();
+            markers_used = weights.iter().filter(|&&w| w > 0.0).count();
+            let m = markers_used.max(1) as f64;
+
+            for i in 0..n_individuals {
+                for k in i..n_individuals {
+                    let mut sum = 0.0;
+                    for j in 0..n_markers {
+                        sum += z[i * n_markers + j] * weights[j] * z[k * n_markers + j];
+                    }
+                    let g_ik = sum / m;
+                    grm[i * n_individuals + k] = g_ik;
+                    grm[k * n_individuals + i] = g_ik;
+                }
+            }
+        }
+    }
+
+    let mut diag_sum = 0.0;
+    let mut off_diag_sum = 0.0;
+    let mut off_diag_count = 0;
+    for i in 0..n_individuals {
+        diag_sum += grm[i * n_individuals + i];
+        for j in (i + 1)..n_individuals {
+            off_diag_sum += grm[i * n_individuals + j];
+            off_diag_count += 1;
+        }
+    }
+
+    let result = GRMResult {
+        matrix: grm,
+        n_samples: n_individuals,
+        n_markers_used: markers_used,
+        mean_diagonal: diag_sum / n_individuals as f64,
+        mean_off_diagonal: if off_diag_count > 0 { off_diag_sum / off_diag_count as f64 } else { 0.0 },
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+/// Pedigree-free relatedness estimation directly from marker dosages: derives
+/// each marker's allele frequency from the data itself, then applies the
+/// same Queller & Goodnight (1989) frequency-centered estimator as
+/// [`queller_goodnight`]. Use this when no pedigree or precomputed allele
+/// frequencies are available.
+#[wasm_bindgen]
+pub fn build_relatedness_qg(genotypes: &[i32], n_samples: usize, n_markers: usize) -> Vec<f64> {
+    let mut freqs = vec![0.0; n_markers];
+    for j in 0..n_markers {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for i in 0..n_samples {
+            let geno = genotypes[i * n_markers + j];
+            if geno >= 0 {
+                sum += geno as f64;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            freqs[j] = sum / (2.0 * count as f64);
+        }
+    }
+
+    queller_goodnight(genotypes, n_samples, n_markers, &freqs)
+}
+
 /// Calculate pedigree-based relationship matrix (A-matrix)
 #[wasm_bindgen]
 pub fn calculate_a_matrix(sire_ids: &[i32], dam_ids: &[i32]) -> Vec<f64> {
@@ -150,6 +331,108 @@ pub fn calculate_a_matrix(sire_ids: &[i32], dam_ids: &[i32]) -> Vec<f64> {
     a
 }
 
+/// Sparse matrix in Compressed Sparse Row (CSR) format.
+///
+/// Used for the pedigree A-inverse, which is extremely sparse (a handful of
+/// nonzero entries per row even for millions of animals) but would be
+/// infeasible to hold as the dense `&[f64]` slices the rest of this module
+/// uses. `row_ptr` has `n_rows + 1` entries; row `i`'s nonzeros are
+/// `values[row_ptr[i]..row_ptr[i + 1]]` at columns
+/// `col_indices[row_ptr[i]..row_ptr[i + 1]]`.
+#[derive(Debug, Clone)]
+pub(crate) struct SparseMatrix {
+    pub values: Vec<f64>,
+    pub col_indices: Vec<usize>,
+    pub row_ptr: Vec<usize>,
+    pub n_rows: usize,
+    pub n_cols: usize,
+}
+
+impl SparseMatrix {
+    /// Number of stored (explicit) nonzero entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Sparse matrix-vector product `y = A * x`.
+    pub(crate) fn spmv(&self, x: &[f64]) -> Vec<f64> {
+        let mut y = vec![0.0; self.n_rows];
+        for i in 0..self.n_rows {
+            let mut sum = 0.0;
+            for k in self.row_ptr[i]..self.row_ptr[i + 1] {
+                sum += self.values[k] * x[self.col_indices[k]];
+            }
+            y[i] = sum;
+        }
+        y
+    }
+}
+
+/// Build the pedigree A-inverse directly in CSR form from pedigree triples
+/// (`sire_ids[i]`/`dam_ids[i]` are the parents of animal `i`; a negative id
+/// means unknown), using Henderson's rules for a non-inbred base population:
+/// each animal contributes `alpha = 1 / d` to its own diagonal and,
+/// for each known parent, `-0.5 * alpha` to the animal-parent off-diagonal
+/// and `0.25 * alpha` to the parent's diagonal (plus `0.25 * alpha` between
+/// the two parents when both are known), where `d` is the Mendelian
+/// sampling variance (`0.5` with two known parents, `0.75` with one, `1.0`
+/// with none). Callers never have to construct the dense A-matrix to get
+/// its inverse.
+pub(crate) fn build_a_inverse_sparse(sire_ids: &[i32], dam_ids: &[i32]) -> SparseMatrix {
+    let n = sire_ids.len();
+    let mut rows: Vec<std::collections::BTreeMap<usize, f64>> =
+        vec![std::collections::BTreeMap::new(); n];
+
+    for i in 0..n {
+        let sire = sire_ids[i];
+        let dam = dam_ids[i];
+        let s = if sire >= 0 && (sire as usize) < n { Some(sire as usize) } else { None };
+        let d = if dam >= 0 && (dam as usize) < n { Some(dam as usize) } else { None };
+
+        let mendelian_var = match (s, d) {
+            (Some(_), Some(_)) => 0.5,
+            (Some(_), None) | (None, Some(_)) => 0.75,
+            (None, None) => 1.0,
+        };
+        let alpha = 1.0 / mendelian_var;
+
+        *rows[i].entry(i).or_insert(0.0) += alpha;
+        if let Some(s) = s {
+            *rows[i].entry(s).or_insert(0.0) += -0.5 * alpha;
+            *rows[s].entry(i).or_insert(0.0) += -0.5 * alpha;
+            *rows[s].entry(s).or_insert(0.0) += 0.25 * alpha;
+        }
+        if let Some(d) = d {
+            *rows[i].entry(d).or_insert(0.0) += -0.5 * alpha;
+            *rows[d].entry(i).or_insert(0.0) += -0.5 * alpha;
+            *rows[d].entry(d).or_insert(0.0) += 0.25 * alpha;
+        }
+        if let (Some(s), Some(d)) = (s, d) {
+            *rows[s].entry(d).or_insert(0.0) += 0.25 * alpha;
+            *rows[d].entry(s).or_insert(0.0) += 0.25 * alpha;
+        }
+    }
+
+    let mut values = Vec::new();
+    let mut col_indices = Vec::new();
+    let mut row_ptr = vec![0usize; n + 1];
+    for (i, row) in rows.iter().enumerate() {
+        for (&j, &v) in row {
+            col_indices.push(j);
+            values.push(v);
+        }
+        row_ptr[i + 1] = values.len();
+    }
+
+    SparseMatrix {
+        values,
+        col_indices,
+        row_ptr,
+        n_rows: n,
+        n_cols: n,
+    }
+}
+
 /// Calculate kinship coefficient between two individuals
 #[wasm_bindgen]
 pub fn calculate_kinship(geno1: &[i32], geno2: &[i32]) -> f64 {
@@ -214,6 +497,303 @@ pub fn calculate_ibs_matrix(genotypes: &[i32], n_samples: usize, n_markers: usiz
     ibs
 }
 
+/// Queller & Goodnight (1989) symmetric relatedness estimator: allele
+/// sharing weighted by population allele frequency, an alternative to plain
+/// IBS kinship.
+///
+/// For each marker `l`, center each individual's dosage on the supplied
+/// `allele_freqs[l]` (`z_i = g_i - 2*p_l`). The x-referenced coefficient
+/// sums "shared minus expected" numerators (`z_x * z_y`) and x's own
+/// frequency-deviation denominators (`z_x * z_x`) across *all* markers
+/// before dividing (locus sums, not per-locus ratios); the y-referenced
+/// coefficient does the same with x and y swapped. The reported relatedness
+/// is the average of the two, which is symmetric even though each
+/// reference's own normalization is not.
+#[wasm_bindgen]
+pub fn queller_goodnight(genotypes: &[i32], n_samples: usize, n_markers: usize, allele_freqs: &[f64]) -> Vec<f64> {
+    let mut relatedness = vec![0.0; n_samples * n_samples];
+
+    for x in 0..n_samples {
+        relatedness[x * n_samples + x] = 1.0;
+
+        for y in (x + 1)..n_samples {
+            let mut num_sum = 0.0;
+            let mut denom_x = 0.0;
+            let mut denom_y = 0.0;
+
+            for l in 0..n_markers {
+                let gx = genotypes[x * n_markers + l];
+                let gy = genotypes[y * n_markers + l];
+                if gx < 0 || gy < 0 {
+                    continue;
+                }
+                let p = allele_freqs[l];
+                let zx = gx as f64 - 2.0 * p;
+                let zy = gy as f64 - 2.0 * p;
+
+                num_sum += zx * zy;
+                denom_x += zx * zx;
+                denom_y += zy * zy;
+            }
+
+            let r_x = if denom_x > 0.0 { num_sum / denom_x } else { 0.0 };
+            let r_y = if denom_y > 0.0 { num_sum / denom_y } else { 0.0 };
+            let r_xy = (r_x + r_y) / 2.0;
+
+            relatedness[x * n_samples + y] = r_xy;
+            relatedness[y * n_samples + x] = r_xy;
+        }
+    }
+
+    relatedness
+}
+
+/// Invert a small `n x n` matrix (row-major) via Gauss-Jordan elimination
+/// with partial pivoting. Returns `None` if the matrix is singular.
+pub(crate) fn invert_small_matrix(matrix: &[f64], n: usize) -> Option<Vec<f64>> {
+    let mut aug = vec![0.0; n * 2 * n];
+    for i in 0..n {
+        for j in 0..n {
+            aug[i * 2 * n + j] = matrix[i * n + j];
+        }
+        aug[i * 2 * n + n + i] = 1.0;
+    }
+
+    for col in 0..n {
+        let pivot_row = (col..n).max_by(|&a, &b| {
+            aug[a * 2 * n + col].abs().partial_cmp(&aug[b * 2 * n + col].abs()).unwrap()
+        })?;
+        if aug[pivot_row * 2 * n + col].abs() < 1e-12 {
+            return None;
+        }
+        aug.swap(col * 2 * n..col * 2 * n + 2 * n, pivot_row * 2 * n..pivot_row * 2 * n + 2 * n);
+
+        let pivot = aug[col * 2 * n + col];
+        for j in 0..2 * n {
+            aug[col * 2 * n + j] /= pivot;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = aug[row * 2 * n + col];
+            if factor != 0.0 {
+                for j in 0..2 * n {
+                    aug[row * 2 * n + j] -= factor * aug[col * 2 * n + j];
+                }
+            }
+        }
+    }
+
+    let mut inv = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..n {
+            inv[i * n + j] = aug[i * 2 * n + n + j];
+        }
+    }
+    Some(inv)
+}
+
+/// Build the single-step relationship inverse `H⁻¹ = A⁻¹ + [[0, 0], [0,
+/// G_b⁻¹ − A22⁻¹]]` for single-step GBLUP, where `pedigree_a` (`n_individuals
+/// x n_individuals`) is the full pedigree relationship matrix, `grm`
+/// (`n_genotyped x n_genotyped`) is the genomic relationship matrix for the
+/// animals listed in `genotyped_idx`, `A22` is `pedigree_a`'s sub-block for
+/// those same animals, and `G_b = blend_weight·grm + (1 − blend_weight)·A22`
+/// is blended toward `A22` to keep it on the same scale and guarantee it's
+/// invertible. Returns `None` if any of `pedigree_a`, `G_b`, or `A22` is
+/// singular. Shared by the wasm ssGBLUP prototype and the native
+/// `fortran_ffi::ss_gblup` solver so the blending math lives in one place.
+pub(crate) fn build_h_inverse(
+    pedigree_a: &[f64],
+    grm: &[f64],
+    genotyped_idx: &[usize],
+    n_individuals: usize,
+    blend_weight: f64,
+) -> Option<Vec<f64>> {
+    let n_genotyped = genotyped_idx.len();
+    let a_inv = invert_small_matrix(pedigree_a, n_individuals)?;
+
+    let mut a22 = vec![0.0; n_genotyped * n_genotyped];
+    for gi in 0..n_genotyped {
+        for gj in 0..n_genotyped {
+            a22[gi * n_genotyped + gj] =
+                pedigree_a[genotyped_idx[gi] * n_individuals + genotyped_idx[gj]];
+        }
+    }
+
+    let mut g_blend = vec![0.0; n_genotyped * n_genotyped];
+    for k in 0..n_genotyped * n_genotyped {
+        g_blend[k] = blend_weight * grm[k] + (1.0 - blend_weight) * a22[k];
+    }
+
+    let g_blend_inv = invert_small_matrix(&g_blend, n_genotyped)?;
+    let a22_inv = invert_small_matrix(&a22, n_genotyped)?;
+
+    let mut h_inv = a_inv;
+    for gi in 0..n_genotyped {
+        for gj in 0..n_genotyped {
+            let delta = g_blend_inv[gi * n_genotyped + gj] - a22_inv[gi * n_genotyped + gj];
+            let i = genotyped_idx[gi];
+            let j = genotyped_idx[gj];
+            h_inv[i * n_individuals + j] += delta;
+        }
+    }
+
+    Some(h_inv)
+}
+
+/// Log-determinant of a small `n x n` matrix via Gaussian elimination with
+/// partial pivoting (sign dropped — only used inside restricted
+/// log-likelihoods where the determinant is of a positive-definite matrix).
+pub(crate) fn log_det_small(matrix: &[f64], n: usize) -> f64 {
+    let mut a = matrix.to_vec();
+    let mut log_det = 0.0;
+
+    for col in 0..n {
+        let mut pivot_row = col;
+        let mut max_val = a[col * n + col].abs();
+        for row in (col + 1)..n {
+            if a[row * n + col].abs() > max_val {
+                max_val = a[row * n + col].abs();
+                pivot_row = row;
+            }
+        }
+        if pivot_row != col {
+            a.swap(col * n..col * n + n, pivot_row * n..pivot_row * n + n);
+        }
+
+        let pivot = a[col * n + col];
+        if pivot.abs() < 1e-300 {
+            return f64::NEG_INFINITY;
+        }
+        log_det += pivot.abs().ln();
+
+        for row in (col + 1)..n {
+            let factor = a[row * n + col] / pivot;
+            for j in col..n {
+                a[row * n + j] -= factor * a[col * n + j];
+            }
+        }
+    }
+
+    log_det
+}
+
+/// Result of PC-Relate kinship and inbreeding estimation
+#[derive(Serialize, Deserialize)]
+pub struct PcRelateResult {
+    pub kinship: Vec<f64>,
+    pub inbreeding: Vec<f64>,
+}
+
+/// PC-Relate: kinship estimation that corrects for population structure.
+///
+/// `genotypes` is a row-major `n_samples * n_markers` matrix of 0/1/2 calls
+/// (missing genotypes are negative; they are left out of the kinship sums,
+/// and in the frequency regression they add nothing to `X'y` while `X'X`
+/// still covers every sample). `pcs` is a
+/// row-major `n_samples * n_pcs` matrix of principal-component scores.
+///
+/// For each marker, individual-specific allele frequencies `mu_ij` are fit
+/// by ordinary least squares of genotype on `[1, pc_1, ..., pc_k]` (the same
+/// design matrix for every marker, so `(X'X)` is inverted once), then halved
+/// and clamped to `[0.01, 0.99]`. Residuals `r_ij = g_ij - 2*mu_ij` give the
+/// kinship between `a` and `b`:
+/// `Σ_j r_aj·r_bj / (4 Σ_j √(μ_aj(1-μ_aj)·μ_bj(1-μ_bj)))`,
+/// with self-kinship on the diagonal yielding inbreeding `F = 2*kinship - 1`.
+#[wasm_bindgen]
+pub fn pcrelate(
+    genotypes: &[i32],
+    n_samples: usize,
+    n_markers: usize,
+    pcs: &[f64],
+    n_pcs: usize,
+) -> JsValue {
+    let n_params = n_pcs + 1;
+
+    let design_row = |i: usize| -> Vec<f64> {
+        let mut row = vec![1.0; n_params];
+        row[1..].copy_from_slice(&pcs[i * n_pcs..i * n_pcs + n_pcs]);
+        row
+    };
+
+    let mut xtx = vec![0.0; n_params * n_params];
+    for i in 0..n_samples {
+        let row = design_row(i);
+        for a in 0..n_params {
+            for b in 0..n_params {
+                xtx[a * n_params + b] += row[a] * row[b];
+            }
+        }
+    }
+
+    let xtx_inv = match invert_small_matrix(&xtx, n_params) {
+        Some(inv) => inv,
+        None => return serde_wasm_bindgen::to_value(&PcRelateResult { kinship: vec![], inbreeding: vec![] }).unwrap(),
+    };
+
+    // Individual-specific allele frequency mu[sample][marker]
+    let mut mu = vec![0.0; n_samples * n_markers];
+    for j in 0..n_markers {
+        let mut xty = vec![0.0; n_params];
+        for i in 0..n_samples {
+            let geno = genotypes[i * n_markers + j];
+            if geno < 0 {
+                continue;
+            }
+            let row = design_row(i);
+            for a in 0..n_params {
+                xty[a] += row[a] * geno as f64;
+            }
+        }
+
+        let mut beta = vec![0.0; n_params];
+        for a in 0..n_params {
+            for b in 0..n_params {
+                beta[a] += xtx_inv[a * n_params + b] * xty[b];
+            }
+        }
+
+        for i in 0..n_samples {
+            let row = design_row(i);
+            let pred: f64 = row.iter().zip(beta.iter()).map(|(x, b)| x * b).sum();
+            mu[i * n_markers + j] = (pred / 2.0).clamp(0.01, 0.99);
+        }
+    }
+
+    let mut kinship = vec![0.0; n_samples * n_samples];
+    for a in 0..n_samples {
+        for b in a..n_samples {
+            let mut numerator = 0.0;
+            let mut denominator = 0.0;
+            for j in 0..n_markers {
+                let ga = genotypes[a * n_markers + j];
+                let gb = genotypes[b * n_markers + j];
+                if ga < 0 || gb < 0 {
+                    continue;
+                }
+                let mu_a = mu[a * n_markers + j];
+                let mu_b = mu[b * n_markers + j];
+                let r_a = ga as f64 - 2.0 * mu_a;
+                let r_b = gb as f64 - 2.0 * mu_b;
+                numerator += r_a * r_b;
+                denominator += (mu_a * (1.0 - mu_a) * mu_b * (1.0 - mu_b)).sqrt();
+            }
+
+            let k = if denominator > 0.0 { numerator / (4.0 * denominator) } else { 0.0 };
+            kinship[a * n_samples + b] = k;
+            kinship[b * n_samples + a] = k;
+        }
+    }
+
+    let inbreeding: Vec<f64> = (0..n_samples).map(|i| 2.0 * kinship[i * n_samples + i] - 1.0).collect();
+
+    let result = PcRelateResult { kinship, inbreeding };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 /// Eigenvalue decomposition result
 #[derive(Serialize, Deserialize)]
 pub struct EigenResult {
@@ -222,65 +802,239 @@ pub struct EigenResult {
     pub cumulative_variance: Vec<f64>,
 }
 
-/// Calculate eigenvalues of a symmetric matrix (power iteration method)
-/// Returns top k eigenvalues
+/// Calculate the top-k eigenvalues of a symmetric matrix via cyclic Jacobi
+/// rotation (see [`eigen_decompose`] for the accompanying eigenvectors).
 #[wasm_bindgen]
 pub fn calculate_eigenvalues(matrix: &[f64], n: usize, k: usize) -> JsValue {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    
-    let mut eigenvalues = Vec::with_capacity(k);
-    let mut deflated = matrix.to_vec();
-    
-    for _ in 0..k.min(n) {
-        // Initialize random vector
-        let mut v: Vec<f64> = (0..n).map(|_| rng.gen::<f64>() - 0.5).collect();
-        let mut norm: f64 = v.iter().map(|x| x * x).sum::<f64>().sqrt();
-        for x in &mut v {
-            *x /= norm;
-        }
-
-        // Power iteration
-        for _ in 0..100 {
-            // Matrix-vector multiplication
-            let mut av = vec![0.0; n];
-            for i in 0..n {
+    let k = k.min(n);
+    let (values, vectors) = jacobi_eigen(matrix, n, 1e-10, 100);
+    let (eigenvalues, _) = sort_and_select(values, vectors, n, n, k);
+
+    let total: f64 = eigenvalues.iter().sum();
+    let explained: Vec<f64> = eigenvalues.iter().map(|e| if total != 0.0 { e / total * 100.0 } else { 0.0 }).collect();
+    let mut cumulative = Vec::with_capacity(k);
+    let mut cum = 0.0;
+    for e in &explained {
+        cum += e;
+        cumulative.push(cum);
+    }
+
+    let result = EigenResult {
+        eigenvalues,
+        explained_variance: explained,
+        cumulative_variance: cumulative,
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+/// Cyclic Jacobi rotation method for an `n x n` symmetric matrix. Each
+/// sweep walks every off-diagonal pair `(p,q)` in row-major order, zeroing
+/// `a_pq` with a Givens rotation (`θ = (a_qq-a_pp)/(2a_pq)`,
+/// `t = sign(θ)/(|θ|+√(θ²+1))`, `c = 1/√(t²+1)`, `s = tc`), accumulating the
+/// rotations into the eigenvector matrix. Sweeps stop once the off-diagonal
+/// Frobenius norm drops below `tol` or `max_sweeps` is reached. Returns
+/// `(eigenvalues, eigenvectors)` with eigenvectors as columns of a
+/// row-major `n x n` matrix.
+pub(crate) fn jacobi_eigen(matrix: &[f64], n: usize, tol: f64, max_sweeps: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut a = matrix.to_vec();
+    let mut v = vec![0.0; n * n];
+    for i in 0..n {
+        v[i * n + i] = 1.0;
+    }
+
+    for _ in 0..max_sweeps {
+        let mut off_norm_sq = 0.0;
+        for p in 0..n {
+            for q in (p + 1)..n {
+                off_norm_sq += a[p * n + q] * a[p * n + q];
+            }
+        }
+        if (2.0 * off_norm_sq).sqrt() < tol {
+            break;
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let apq = a[p * n + q];
+                if apq.abs() < 1e-15 {
+                    continue;
+                }
+
+                let theta = (a[q * n + q] - a[p * n + p]) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for i in 0..n {
+                    let aip = a[i * n + p];
+                    let aiq = a[i * n + q];
+                    a[i * n + p] = c * aip - s * aiq;
+                    a[i * n + q] = s * aip + c * aiq;
+                }
                 for j in 0..n {
-                    av[i] += deflated[i * n + j] * v[j];
+                    let apj = a[p * n + j];
+                    let aqj = a[q * n + j];
+                    a[p * n + j] = c * apj - s * aqj;
+                    a[q * n + j] = s * apj + c * aqj;
+                }
+                for i in 0..n {
+                    let vip = v[i * n + p];
+                    let viq = v[i * n + q];
+                    v[i * n + p] = c * vip - s * viq;
+                    v[i * n + q] = s * vip + c * viq;
                 }
             }
+        }
+    }
+
+    let eigenvalues: Vec<f64> = (0..n).map(|i| a[i * n + i]).collect();
+    (eigenvalues, v)
+}
+
+/// Sort `n_full` eigenpairs descending by eigenvalue and keep the top `k`.
+/// `vectors` is row-major `n_rows x n_full` (columns are eigenvectors).
+fn sort_and_select(values: Vec<f64>, vectors: Vec<f64>, n_rows: usize, n_full: usize, k: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut idx: Vec<usize> = (0..n_full).collect();
+    idx.sort_by(|&a, &b| values[b].partial_cmp(&values[a]).unwrap());
+
+    let top_values: Vec<f64> = idx.iter().take(k).map(|&i| values[i]).collect();
+    let mut top_vectors = vec![0.0; n_rows * k];
+    for (col, &i) in idx.iter().take(k).enumerate() {
+        for row in 0..n_rows {
+            top_vectors[row * k + col] = vectors[row * n_full + i];
+        }
+    }
+
+    (top_values, top_vectors)
+}
+
+/// `a` (`a_rows x a_cols`) times `b` (`a_cols x b_cols`), both row-major.
+fn matmul(a: &[f64], a_rows: usize, a_cols: usize, b: &[f64], b_cols: usize) -> Vec<f64> {
+    let mut out = vec![0.0; a_rows * b_cols];
+    for i in 0..a_rows {
+        for k in 0..a_cols {
+            let aik = a[i * a_cols + k];
+            if aik == 0.0 {
+                continue;
+            }
+            for j in 0..b_cols {
+                out[i * b_cols + j] += aik * b[k * b_cols + j];
+            }
+        }
+    }
+    out
+}
 
-            // Normalize
-            norm = av.iter().map(|x| x * x).sum::<f64>().sqrt();
-            if norm < 1e-10 {
-                break;
+/// `qᵀ` (`q` is `rows x cols`) times `m` (`rows x m_cols`), giving `cols x
+/// m_cols`.
+fn mat_t_mul(q: &[f64], rows: usize, cols: usize, m: &[f64], m_cols: usize) -> Vec<f64> {
+    let mut out = vec![0.0; cols * m_cols];
+    for a in 0..cols {
+        for i in 0..rows {
+            let qia = q[i * cols + a];
+            if qia == 0.0 {
+                continue;
             }
-            for i in 0..n {
-                v[i] = av[i] / norm;
+            for b in 0..m_cols {
+                out[a * m_cols + b] += qia * m[i * m_cols + b];
             }
         }
+    }
+    out
+}
+
+/// Orthonormalize the columns of `a` (`rows x cols`, row-major) via
+/// (modified) Gram-Schmidt, returning the thin-QR factor `Q`.
+fn qr_thin(a: &[f64], rows: usize, cols: usize) -> Vec<f64> {
+    let mut q = a.to_vec();
 
-        // Eigenvalue = v' * A * v
-        let mut av = vec![0.0; n];
-        for i in 0..n {
-            for j in 0..n {
-                av[i] += deflated[i * n + j] * v[j];
+    for j in 0..cols {
+        for k in 0..j {
+            let mut dot = 0.0;
+            for i in 0..rows {
+                dot += q[i * cols + k] * q[i * cols + j];
+            }
+            for i in 0..rows {
+                q[i * cols + j] -= dot * q[i * cols + k];
             }
         }
-        let eigenvalue: f64 = v.iter().zip(av.iter()).map(|(a, b)| a * b).sum();
-        eigenvalues.push(eigenvalue.abs());
 
-        // Deflate matrix: A = A - Î» * v * v'
-        for i in 0..n {
-            for j in 0..n {
-                deflated[i * n + j] -= eigenvalue * v[i] * v[j];
+        let norm: f64 = (0..rows).map(|i| q[i * cols + j] * q[i * cols + j]).sum::<f64>().sqrt();
+        if norm > 1e-10 {
+            for i in 0..rows {
+                q[i * cols + j] /= norm;
             }
         }
     }
 
-    // Calculate explained variance
+    q
+}
+
+/// Sample a standard normal variate via the Box-Muller transform (the crate
+/// depends on `rand` but not `rand_distr`, so this avoids a new dependency).
+pub(crate) fn gaussian_sample(rng: &mut impl rand::Rng) -> f64 {
+    let u1: f64 = rng.gen::<f64>().max(1e-12);
+    let u2: f64 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Randomized SVD fast path for [`eigen_decompose`] on large `n`: sketch
+/// `A` with a Gaussian random matrix `Ω` (`n x (k+10)`), form `Y = AΩ`,
+/// orthonormalize to `Q` via QR, eigendecompose the small `B = QᵀAQ` with
+/// the same Jacobi method, and lift the eigenvectors back with `Q`.
+fn randomized_eigen(matrix: &[f64], n: usize, k: usize) -> (Vec<f64>, Vec<f64>) {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+
+    let sketch_size = (k + 10).min(n);
+
+    let mut omega = vec![0.0; n * sketch_size];
+    for x in omega.iter_mut() {
+        *x = gaussian_sample(&mut rng);
+    }
+
+    let y = matmul(matrix, n, n, &omega, sketch_size);
+    let q = qr_thin(&y, n, sketch_size);
+
+    let aq = matmul(matrix, n, n, &q, sketch_size);
+    let b = mat_t_mul(&q, n, sketch_size, &aq, sketch_size);
+
+    let (b_values, b_vectors) = jacobi_eigen(&b, sketch_size, 1e-10, 100);
+    let full_vectors = matmul(&q, n, sketch_size, &b_vectors, sketch_size);
+
+    sort_and_select(b_values, full_vectors, n, sketch_size, k)
+}
+
+/// Eigendecomposition result including eigenvectors (PCA sample scores)
+#[derive(Serialize, Deserialize)]
+pub struct EigenDecomposeResult {
+    pub eigenvalues: Vec<f64>,
+    /// Row-major `n x k` matrix; column `i` is the eigenvector for
+    /// `eigenvalues[i]`.
+    pub eigenvectors: Vec<f64>,
+    pub explained_variance: Vec<f64>,
+    pub cumulative_variance: Vec<f64>,
+}
+
+/// Full eigendecomposition with eigenvectors, for PCA sample scores and
+/// population-structure plots. Uses cyclic Jacobi rotation for `n <= 200`;
+/// above that, switches to a randomized-SVD sketch (see
+/// [`randomized_eigen`]) so the cost is `O(n²k)` (a few `n x n` by
+/// `n x (k+10)` products) instead of cubic in `n`. Eigenpairs are returned sorted by descending eigenvalue.
+#[wasm_bindgen]
+pub fn eigen_decompose(matrix: &[f64], n: usize, k: usize) -> JsValue {
+    let k = k.min(n);
+
+    let (eigenvalues, eigenvectors) = if n > 200 {
+        randomized_eigen(matrix, n, k)
+    } else {
+        let (values, vectors) = jacobi_eigen(matrix, n, 1e-10, 100);
+        sort_and_select(values, vectors, n, n, k)
+    };
+
     let total: f64 = eigenvalues.iter().sum();
-    let explained: Vec<f64> = eigenvalues.iter().map(|e| e / total * 100.0).collect();
+    let explained: Vec<f64> = eigenvalues.iter().map(|e| if total != 0.0 { e / total * 100.0 } else { 0.0 }).collect();
     let mut cumulative = Vec::with_capacity(k);
     let mut cum = 0.0;
     for e in &explained {
@@ -288,11 +1042,162 @@ pub fn calculate_eigenvalues(matrix: &[f64], n: usize, k: usize) -> JsValue {
         cumulative.push(cum);
     }
 
-    let result = EigenResult {
+    let result = EigenDecomposeResult {
         eigenvalues,
+        eigenvectors,
         explained_variance: explained,
         cumulative_variance: cumulative,
     };
 
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
+
+/// A strided N-dimensional array, built on `ndarray`'s dynamic-rank `ArrayD`
+/// so the rest of the crate can stop threading row-major flat-slice indexing
+/// (`ind_idx * n_markers + marker_idx`) through every matrix function by hand.
+/// Slicing selects through a strided view and returns an owned copy of the
+/// selected elements; broadcasting follows NumPy's rules of right-aligning
+/// shapes and stretching size-1 dimensions.
+#[derive(Clone, Debug)]
+pub struct NdArray(ArrayD<f32>);
+
+impl NdArray {
+    /// Build a C-contiguous array from flat row-major `data` and its `shape`.
+    /// Returns `None` if `data.len()` doesn't match the shape's element count.
+    pub fn from_shape(data: Vec<f32>, shape: Vec<usize>) -> Option<Self> {
+        ArrayD::from_shape_vec(IxDyn(&shape), data).ok().map(NdArray)
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        self.0.shape()
+    }
+
+    /// Materialize this array (or view) into a flat, row-major `Vec<f32>`.
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.0.iter().cloned().collect()
+    }
+
+    /// Take a NumPy-style `[start:stop:step]` range along every axis. The
+    /// ranges are applied to a view by adjusting offset/strides, and the
+    /// selected elements are then copied into a new owned array; the
+    /// returned `NdArray` does not share storage with `self`.
+    pub fn slice(&self, ranges: &[(isize, isize, isize)]) -> NdArray {
+        let mut view = self.0.view();
+        for (axis, &(start, stop, step)) in ranges.iter().enumerate() {
+            view.slice_axis_inplace(Axis(axis), Slice::new(start, Some(stop), step));
+        }
+        NdArray(view.to_owned())
+    }
+
+    /// Broadcast `self` and `other` to a common shape and combine them
+    /// elementwise with `op`. Backs the `ndarray_add`/`ndarray_sub`/
+    /// `ndarray_mul`/`ndarray_div` WASM bindings below and
+    /// `genomics_kernel::gblup::calculate_g_matrix`'s `z = geno - 2*freq`
+    /// centering and `G / scale` steps, so those don't each hand-roll their
+    /// own broadcast rules.
+    pub fn broadcast_op(&self, other: &NdArray, op: impl Fn(f32, f32) -> f32) -> Option<NdArray> {
+        let shape = broadcast_shape(self.0.shape(), other.0.shape())?;
+        let a = self.0.broadcast(IxDyn(&shape))?;
+        let b = other.0.broadcast(IxDyn(&shape))?;
+        Some(NdArray(Zip::from(&a).and(&b).map_collect(|&x, &y| op(x, y))))
+    }
+}
+
+/// Compute the NumPy-style broadcast shape of two shapes: right-align them
+/// and require each dimension to either match or be 1. Returns `None` if
+/// some dimension conflicts.
+pub fn broadcast_shape(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    let n = a.len().max(b.len());
+    let mut shape = vec![1usize; n];
+
+    for i in 0..n {
+        let da = a.len().checked_sub(n - i).and_then(|idx| a.get(idx)).copied().unwrap_or(1);
+        let db = b.len().checked_sub(n - i).and_then(|idx| b.get(idx)).copied().unwrap_or(1);
+
+        shape[i] = if da == db {
+            da
+        } else if da == 1 {
+            db
+        } else if db == 1 {
+            da
+        } else {
+            return None;
+        };
+    }
+
+    Some(shape)
+}
+
+/// Result of a broadcasting `NdArray` elementwise operation.
+#[derive(Serialize, Deserialize)]
+pub struct NdArrayData {
+    pub data: Vec<f32>,
+    pub shape: Vec<usize>,
+}
+
+fn ndarray_elementwise(
+    a: &[f32],
+    a_shape: &[usize],
+    b: &[f32],
+    b_shape: &[usize],
+    op: impl Fn(f32, f32) -> f32,
+) -> JsValue {
+    let result = NdArray::from_shape(a.to_vec(), a_shape.to_vec())
+        .zip(NdArray::from_shape(b.to_vec(), b_shape.to_vec()))
+        .and_then(|(a, b)| a.broadcast_op(&b, op));
+
+    let data = match result {
+        Some(arr) => NdArrayData { data: arr.to_vec(), shape: arr.shape().to_vec() },
+        None => NdArrayData { data: vec![], shape: vec![] },
+    };
+
+    serde_wasm_bindgen::to_value(&data).unwrap()
+}
+
+/// Elementwise add with NumPy-style broadcasting (e.g. adding a per-marker
+/// vector to every sample's row).
+#[wasm_bindgen]
+pub fn ndarray_add(a: &[f32], a_shape: &[usize], b: &[f32], b_shape: &[usize]) -> JsValue {
+    ndarray_elementwise(a, a_shape, b, b_shape, |x, y| x + y)
+}
+
+/// Elementwise subtract with NumPy-style broadcasting.
+#[wasm_bindgen]
+pub fn ndarray_sub(a: &[f32], a_shape: &[usize], b: &[f32], b_shape: &[usize]) -> JsValue {
+    ndarray_elementwise(a, a_shape, b, b_shape, |x, y| x - y)
+}
+
+/// Elementwise multiply with NumPy-style broadcasting.
+#[wasm_bindgen]
+pub fn ndarray_mul(a: &[f32], a_shape: &[usize], b: &[f32], b_shape: &[usize]) -> JsValue {
+    ndarray_elementwise(a, a_shape, b, b_shape, |x, y| x * y)
+}
+
+/// Elementwise divide with NumPy-style broadcasting (e.g. `G / scale`).
+#[wasm_bindgen]
+pub fn ndarray_div(a: &[f32], a_shape: &[usize], b: &[f32], b_shape: &[usize]) -> JsValue {
+    ndarray_elementwise(a, a_shape, b, b_shape, |x, y| x / y)
+}
+
+/// Take a NumPy-style `[start:stop:step]` slice along every dimension.
+#[wasm_bindgen]
+pub fn ndarray_slice(
+    data: &[f32],
+    shape: &[usize],
+    starts: &[i32],
+    stops: &[i32],
+    steps: &[i32],
+) -> JsValue {
+    let array = match NdArray::from_shape(data.to_vec(), shape.to_vec()) {
+        Some(array) => array,
+        None => return serde_wasm_bindgen::to_value(&NdArrayData { data: vec![], shape: vec![] }).unwrap(),
+    };
+
+    let ranges: Vec<(isize, isize, isize)> = starts.iter().zip(stops.iter()).zip(steps.iter())
+        .map(|((&start, &stop), &step)| (start as isize, stop as isize, step as isize))
+        .collect();
+
+    let view = array.slice(&ranges);
+    let result = NdArrayData { data: view.to_vec(), shape: view.shape().to_vec() };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
@@ -115,7 +115,10 @@ pub struct FstResult {
     pub per_marker_fst: Vec<f64>,
 }
 
-/// Calculate Fst between populations
+/// Calculate Fst (and Fis/Fit) between populations using the Weir &
+/// Cockerham (1984) ANOVA variance-component estimator, which — unlike a
+/// plain Hs/Ht ratio — correctly accounts for unequal population sample
+/// sizes and separately resolves within- and among-individual structure.
 #[wasm_bindgen]
 pub fn calculate_fst(
     genotypes: &[i32],
@@ -139,14 +142,15 @@ pub fn calculate_fst(
     }
 
     let mut per_marker_fst = Vec::with_capacity(n_markers);
-    let mut total_hs = 0.0;
-    let mut total_ht = 0.0;
-    let mut valid_markers = 0;
+    let mut sum_a = 0.0;
+    let mut sum_b = 0.0;
+    let mut sum_c = 0.0;
 
     for j in 0..n_markers {
-        // Calculate allele frequencies per population
-        let mut pop_freqs = vec![0.0; n_pops];
-        let mut pop_counts = vec![0usize; n_pops];
+        // Per-population sample size (diploid individuals), allele
+        // frequency, and observed heterozygosity for this marker.
+        let mut pop_n = vec![0usize; n_pops];
+        let mut pop_p = vec![0.0; n_pops];
         let mut pop_het = vec![0.0; n_pops];
 
         for i in 0..n_samples {
@@ -154,67 +158,251 @@ pub fn calculate_fst(
             if geno < 0 { continue; }
 
             let pop_idx = pops.iter().position(|&p| p == population_ids[i]).unwrap();
-            pop_freqs[pop_idx] += geno as f64;
-            pop_counts[pop_idx] += 1;
+            pop_p[pop_idx] += geno as f64;
+            pop_n[pop_idx] += 1;
             if geno == 1 {
                 pop_het[pop_idx] += 1.0;
             }
         }
 
-        // Convert to frequencies
-        let mut valid_pops = 0;
+        let mut r = 0;
         for k in 0..n_pops {
-            if pop_counts[k] > 0 {
-                pop_freqs[k] /= 2.0 * pop_counts[k] as f64;
-                pop_het[k] /= pop_counts[k] as f64;
-                valid_pops += 1;
+            if pop_n[k] > 0 {
+                pop_p[k] /= 2.0 * pop_n[k] as f64;
+                pop_het[k] /= pop_n[k] as f64;
+                r += 1;
             }
         }
 
-        if valid_pops < 2 { continue; }
+        if r < 2 { continue; }
+
+        let sum_ni: f64 = pop_n.iter().map(|&n| n as f64).sum();
+        let sum_ni2: f64 = pop_n.iter().map(|&n| (n as f64).powi(2)).sum();
+        let n_bar = sum_ni / r as f64;
+        let n_c = (sum_ni - sum_ni2 / sum_ni) / (r - 1) as f64;
+
+        let p_bar: f64 = (0..n_pops)
+            .filter(|&k| pop_n[k] > 0)
+            .map(|k| pop_n[k] as f64 * pop_p[k])
+            .sum::<f64>()
+            / sum_ni;
+
+        let s_sq: f64 = (0..n_pops)
+            .filter(|&k| pop_n[k] > 0)
+            .map(|k| pop_n[k] as f64 * (pop_p[k] - p_bar).powi(2))
+            .sum::<f64>()
+            / ((r - 1) as f64 * n_bar);
+
+        let h_bar: f64 = (0..n_pops)
+            .filter(|&k| pop_n[k] > 0)
+            .map(|k| pop_n[k] as f64 * pop_het[k])
+            .sum::<f64>()
+            / sum_ni;
+
+        if n_c <= 0.0 || n_bar <= 1.0 { continue; }
+
+        let r_f = r as f64;
+        let a = (n_bar / n_c)
+            * (s_sq - (1.0 / (n_bar - 1.0)) * (p_bar * (1.0 - p_bar) - ((r_f - 1.0) / r_f) * s_sq - h_bar / 4.0));
+        let b = (n_bar / (n_bar - 1.0))
+            * (p_bar * (1.0 - p_bar) - ((r_f - 1.0) / r_f) * s_sq - ((2.0 * n_bar - 1.0) / (4.0 * n_bar)) * h_bar);
+        let c = h_bar / 2.0;
+
+        let denom = a + b + c;
+        per_marker_fst.push(if denom > 0.0 { (a / denom).clamp(0.0, 1.0) } else { 0.0 });
+
+        sum_a += a;
+        sum_b += b;
+        sum_c += c;
+    }
 
-        // Calculate Hs (within-population heterozygosity)
-        let mut hs = 0.0;
-        let mut total_n = 0;
-        for k in 0..n_pops {
-            if pop_counts[k] > 0 {
-                let p = pop_freqs[k];
-                hs += 2.0 * p * (1.0 - p) * pop_counts[k] as f64;
-                total_n += pop_counts[k];
-            }
-        }
-        hs /= total_n as f64;
+    let denom = sum_a + sum_b + sum_c;
+    let fst = if denom > 0.0 { (sum_a / denom).clamp(0.0, 1.0) } else { 0.0 };
+    let fit = if denom > 0.0 { (1.0 - sum_c / denom).clamp(-1.0, 1.0) } else { 0.0 };
+    let fis = if sum_b + sum_c > 0.0 { (1.0 - sum_c / (sum_b + sum_c)).clamp(-1.0, 1.0) } else { 0.0 };
 
-        // Calculate Ht (total heterozygosity)
-        let mut p_total = 0.0;
-        for k in 0..n_pops {
-            if pop_counts[k] > 0 {
-                p_total += pop_freqs[k] * pop_counts[k] as f64;
+    let result = FstResult {
+        fst,
+        fis,
+        fit,
+        per_marker_fst,
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+/// Site-frequency-spectrum neutrality test result
+#[derive(Serialize, Deserialize)]
+pub struct NeutralityTestResult {
+    pub n_segregating_sites: usize,
+    pub theta_w: f64,
+    pub theta_pi: f64,
+    pub tajimas_d: f64,
+    pub fu_li_d: f64,
+    pub fu_li_f: f64,
+    pub fay_wu_h: f64,
+    pub zeng_e: f64,
+    /// `true` if `ancestral` was supplied and non-empty, so the spectrum
+    /// could be polarized and `fay_wu_h`/`zeng_e` are meaningful. When
+    /// `false` those two fields are `0.0` and `fu_li_d`/`fu_li_f` fall back
+    /// to treating minor-allele singletons as a proxy for external
+    /// mutations (an approximation of the true, outgroup-polarized count).
+    pub ancestral_polarized: bool,
+}
+
+/// Site-frequency-spectrum neutrality tests: Tajima's D, Fu & Li's D/F, Fay
+/// & Wu's H, and Zeng's E. `genotypes` is row-major `n_samples * n_markers`
+/// dosages (0/1/2, negative = missing); `ancestral` optionally gives, per
+/// marker, which allele (0 or 1) is ancestral, polarizing the spectrum so H
+/// and E can be computed — pass an empty slice to fall back to the folded
+/// spectrum (only `tajimas_d`/`fu_li_d`/`fu_li_f` are then populated).
+///
+/// Sites with any missing genotype are skipped, so every segregating site
+/// contributes a full `n = 2 * n_samples` chromosomes to the spectrum —
+/// this keeps the sample size `n` fixed across sites, which the variance
+/// formulas below (Tajima 1989; Fu & Li 1993; Fay & Wu 2000; Zeng et al.
+/// 2006) assume.
+#[wasm_bindgen]
+pub fn calculate_neutrality_tests(
+    genotypes: &[i32],
+    n_samples: usize,
+    n_markers: usize,
+    ancestral: &[i32],
+) -> JsValue {
+    let n = 2.0 * n_samples as f64;
+    let polarized = !ancestral.is_empty();
+
+    if n_samples < 3 {
+        return serde_wasm_bindgen::to_value(&NeutralityTestResult {
+            n_segregating_sites: 0,
+            theta_w: 0.0,
+            theta_pi: 0.0,
+            tajimas_d: 0.0,
+            fu_li_d: 0.0,
+            fu_li_f: 0.0,
+            fay_wu_h: 0.0,
+            zeng_e: 0.0,
+            ancestral_polarized: false,
+        }).unwrap();
+    }
+
+    // a1 = sum 1/i, a2 = sum 1/i^2, i = 1..n-1 (n = chromosomes).
+    let n_chr = n as usize;
+    let mut a1 = 0.0;
+    let mut a2 = 0.0;
+    for i in 1..n_chr {
+        a1 += 1.0 / i as f64;
+        a2 += 1.0 / (i as f64).powi(2);
+    }
+
+    // Per-segregating-site derived (or, if unpolarized, minor) allele
+    // count `i_j` out of `n` chromosomes, plus a singleton flag used by
+    // Fu & Li's statistics.
+    let mut derived_counts: Vec<f64> = Vec::new();
+    let mut n_singletons = 0usize;
+
+    for j in 0..n_markers {
+        let mut complete = true;
+        let mut derived = 0.0;
+        for i in 0..n_samples {
+            let geno = genotypes[i * n_markers + j];
+            if geno < 0 {
+                complete = false;
+                break;
             }
+            derived += if polarized && ancestral[j] == 1 {
+                2.0 - geno as f64
+            } else {
+                geno as f64
+            };
+        }
+        if !complete {
+            continue;
+        }
+        if derived <= 0.0 || derived >= n {
+            continue;
         }
-        p_total /= total_n as f64;
-        let ht = 2.0 * p_total * (1.0 - p_total);
-
-        // Fst for this marker
-        let fst_marker = if ht > 0.0 { (ht - hs) / ht } else { 0.0 };
-        per_marker_fst.push(fst_marker.max(0.0).min(1.0));
 
-        total_hs += hs;
-        total_ht += ht;
-        valid_markers += 1;
+        let i_j = if polarized { derived } else { derived.min(n - derived) };
+        derived_counts.push(i_j);
+        if i_j == 1.0 {
+            n_singletons += 1;
+        }
     }
 
-    let fst = if total_ht > 0.0 {
-        ((total_ht - total_hs) / total_ht).max(0.0).min(1.0)
+    let s = derived_counts.len();
+    let theta_w = if a1 > 0.0 { s as f64 / a1 } else { 0.0 };
+    let theta_pi: f64 = derived_counts.iter().map(|&i_j| 2.0 * i_j * (n - i_j) / (n * (n - 1.0))).sum();
+
+    // Tajima's D.
+    let b1 = (n + 1.0) / (3.0 * (n - 1.0));
+    let b2 = 2.0 * (n * n + n + 3.0) / (9.0 * n * (n - 1.0));
+    let c1 = b1 - 1.0 / a1;
+    let c2 = b2 - (n + 2.0) / (a1 * n) + a2 / (a1 * a1);
+    let e1 = c1 / a1;
+    let e2 = c2 / (a1 * a1 + a2);
+    let var_d = e1 * s as f64 + e2 * (s as f64) * (s as f64 - 1.0);
+    let tajimas_d = if var_d > 0.0 { (theta_pi - theta_w) / var_d.sqrt() } else { 0.0 };
+
+    // Fu & Li's D and F (1993). `eta_e` is the external-mutation
+    // (singleton) count; without an outgroup we substitute the folded
+    // minor-allele singleton count as an approximation.
+    let eta_e = n_singletons as f64;
+    let an1 = a1 + 1.0 / n;
+    let cn = 2.0 * (n * a1 - 2.0 * (n - 1.0)) / ((n - 1.0) * (n - 2.0));
+    let v_d = 1.0 + (a1 * a1 / (a2 + a1 * a1)) * (cn - (n + 1.0) / (n - 1.0));
+    let u_d = a1 - 1.0 - v_d;
+    let var_fu_li_d = u_d * s as f64 + v_d * (s as f64).powi(2);
+    let fu_li_d = if var_fu_li_d > 0.0 { (s as f64 - a1 * eta_e) / var_fu_li_d.sqrt() } else { 0.0 };
+
+    let dn = cn + (n - 2.0) / (n - 1.0).powi(2)
+        + (2.0 / (n - 1.0)) * (1.5 - (2.0 * an1 - 3.0) / (n - 2.0) - 1.0 / n);
+    let v_f = (dn + 2.0 * (n * n + n + 3.0) / (9.0 * n * (n - 1.0))
+        - 2.0 / (n - 1.0) * (4.0 * a2 - (n + 2.0) / n))
+        / (a1 * a1 + a2);
+    let u_f = (1.0 + (n + 1.0) / (3.0 * (n - 1.0))
+        - 4.0 * (n + 1.0) / ((n - 1.0) * (n - 2.0)) * (an1 - 2.0 * n / (n + 1.0)))
+        / a1
+        - v_f;
+    let var_fu_li_f = u_f * s as f64 + v_f * (s as f64).powi(2);
+    let fu_li_f = if var_fu_li_f > 0.0 { (theta_pi - eta_e) / var_fu_li_f.sqrt() } else { 0.0 };
+
+    // Fay & Wu's H and Zeng's E need the polarized spectrum (theta_H,
+    // theta_L); both are left at 0.0 when unpolarized.
+    let (fay_wu_h, zeng_e) = if polarized {
+        let theta_h: f64 = derived_counts.iter().map(|&i_j| 2.0 * i_j * i_j / (n * (n - 1.0))).sum();
+        let theta_l: f64 = derived_counts.iter().map(|&i_j| i_j / (n - 1.0)).sum();
+
+        // Fay & Wu (2000), variance of theta_pi - theta_H under theta = theta_w.
+        let var_h = (n - 2.0) / (6.0 * (n - 1.0)) * theta_w
+            + (18.0 * n * n * (3.0 * n + 2.0) * a2 - (88.0 * n.powi(3) + 9.0 * n * n - 13.0 * n + 6.0))
+                / (9.0 * n * (n - 1.0).powi(2))
+                * theta_w * theta_w;
+        let h = if var_h > 0.0 { (theta_pi - theta_h) / var_h.sqrt() } else { 0.0 };
+
+        // Zeng et al. (2006), variance of theta_L - theta_w under theta = theta_w.
+        let var_e = (n / (2.0 * (n - 1.0)) - 1.0 / a1) * theta_w
+            + (a2 / (a1 * a1) + 2.0 * (n / (n - 1.0)).powi(2) * a2
+                - 2.0 * (n * a2 - n + 1.0) / ((n - 1.0) * a1)
+                - (3.0 * n + 1.0) / (n - 1.0))
+                * theta_w * theta_w;
+        let e = if var_e > 0.0 { (theta_l - theta_w) / var_e.sqrt() } else { 0.0 };
+
+        (h, e)
     } else {
-        0.0
+        (0.0, 0.0)
     };
 
-    let result = FstResult {
-        fst,
-        fis: 0.0, // Would need individual-level calculation
-        fit: fst, // Simplified
-        per_marker_fst,
+    let result = NeutralityTestResult {
+        n_segregating_sites: s,
+        theta_w,
+        theta_pi,
+        tajimas_d,
+        fu_li_d,
+        fu_li_f,
+        fay_wu_h,
+        zeng_e,
+        ancestral_polarized: polarized,
     };
 
     serde_wasm_bindgen::to_value(&result).unwrap()
@@ -274,6 +462,42 @@ pub fn calculate_genetic_distance(
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
+/// Pairwise relatedness / kinship matrix result
+#[derive(Serialize, Deserialize)]
+pub struct RelatednessResult {
+    pub relatedness_matrix: Vec<f64>,
+    pub n_samples: usize,
+    pub method: String,
+}
+
+/// Pairwise relatedness (coancestry) matrix for parent selection and mating
+/// design, complementing [`calculate_genetic_distance`]. `method` is
+/// `"van_raden"` for the genomic relationship matrix (diagonal entries
+/// estimate individual inbreeding) or `"queller_goodnight"` for the
+/// pedigree-free marker-based relatedness estimator; any other value
+/// defaults to `"van_raden"`.
+#[wasm_bindgen]
+pub fn calculate_relatedness(
+    genotypes: &[i32],
+    n_samples: usize,
+    n_markers: usize,
+    method: &str,
+) -> JsValue {
+    let (matrix, method_name) = if method == "queller_goodnight" {
+        (crate::matrix::build_relatedness_qg(genotypes, n_samples, n_markers), "queller_goodnight")
+    } else {
+        (crate::matrix::vanraden_grm(genotypes, n_samples, n_markers), "van_raden")
+    };
+
+    let result = RelatednessResult {
+        relatedness_matrix: matrix,
+        n_samples,
+        method: method_name.to_string(),
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 /// PCA result
 #[derive(Serialize, Deserialize)]
 pub struct PCAResult {
@@ -4,8 +4,10 @@
 
 use pyo3::prelude::*;
 use pyo3::Py;
-use numpy::{IntoPyArray, PyReadonlyArray1};
+use numpy::{IntoPyArray, PyReadonlyArray1, PyReadonlyArrayDyn};
+use ndarray::{IxDyn, Zip};
 use crate::fortran_ffi;
+use crate::matrix::broadcast_shape;
 
 #[pyfunction]
 pub fn blup(
@@ -143,3 +145,323 @@ pub fn reml_estimate(
         result.log_likelihood,
     ))
 }
+
+#[pyfunction]
+pub fn bivar_reml(
+    _py: Python<'_>,
+    phenotype1: PyReadonlyArray1<f64>,
+    phenotype2: PyReadonlyArray1<f64>,
+    fixed_effects: PyReadonlyArray1<f64>,
+    random_effects: PyReadonlyArray1<f64>,
+    relationship_matrix: PyReadonlyArray1<f64>,
+    genetic_cov_init: [f64; 4],
+    residual_cov_init: [f64; 4],
+    constrain_residual_cov: bool,
+    fixed_genetic_correlation: Option<f64>,
+    max_iter: usize,
+    tolerance: f64,
+    n: usize,
+    p: usize,
+    q: usize,
+) -> PyResult<([f64; 4], [f64; 4], f64, bool, i32, f64)> {
+    let phenotype1 = phenotype1.as_slice()?;
+    let phenotype2 = phenotype2.as_slice()?;
+    let fixed_effects = fixed_effects.as_slice()?;
+    let random_effects = random_effects.as_slice()?;
+    let relationship_matrix = relationship_matrix.as_slice()?;
+
+    let result = fortran_ffi::bivar_reml(
+        phenotype1,
+        phenotype2,
+        fixed_effects,
+        random_effects,
+        relationship_matrix,
+        genetic_cov_init,
+        residual_cov_init,
+        constrain_residual_cov,
+        fixed_genetic_correlation,
+        n,
+        p,
+        q,
+        max_iter,
+        tolerance,
+    ).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    Ok((
+        result.genetic_cov,
+        result.residual_cov,
+        result.genetic_correlation,
+        result.converged,
+        result.iterations,
+        result.log_likelihood,
+    ))
+}
+
+#[pyfunction]
+pub fn ss_gblup(
+    _py: Python<'_>,
+    phenotypes: PyReadonlyArray1<f64>,
+    pedigree_a: PyReadonlyArray1<f64>,
+    grm: PyReadonlyArray1<f64>,
+    genotyped_idx: Vec<usize>,
+    var_additive: f64,
+    var_residual: f64,
+    blend_weight: f64,
+    n_individuals: usize,
+    max_iter: usize,
+    tolerance: f64,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    let phenotypes = phenotypes.as_slice()?;
+    let pedigree_a = pedigree_a.as_slice()?;
+    let grm = grm.as_slice()?;
+
+    let result = fortran_ffi::ss_gblup(
+        phenotypes,
+        pedigree_a,
+        grm,
+        &genotyped_idx,
+        var_additive,
+        var_residual,
+        blend_weight,
+        n_individuals,
+        max_iter,
+        tolerance,
+    ).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let beta = result.beta.into_pyarray(_py).to_owned();
+    let u = result.breeding_values.into_pyarray(_py).to_owned();
+
+    Ok((beta.into(), u.into()))
+}
+
+#[pyfunction]
+pub fn solve_mme_with(
+    _py: Python<'_>,
+    coefficient_matrix: PyReadonlyArray1<f64>,
+    rhs: PyReadonlyArray1<f64>,
+    initial_guess: PyReadonlyArray1<f64>,
+    method: &str,
+    restart: usize,
+    tolerance: f64,
+    max_iter: usize,
+) -> PyResult<(Py<PyAny>, i32, f64)> {
+    let coefficient_matrix = coefficient_matrix.as_slice()?;
+    let rhs = rhs.as_slice()?;
+    let initial_guess = initial_guess.as_slice()?;
+
+    let method_enum = match method {
+        "pcg" => fortran_ffi::SolverMethod::Pcg,
+        "bicgstab" => fortran_ffi::SolverMethod::BiCgStab,
+        "gmres" => fortran_ffi::SolverMethod::Gmres { restart },
+        _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid method")),
+    };
+
+    let (solution, iterations, residual_norm) = fortran_ffi::solve_mme_with(
+        coefficient_matrix,
+        rhs,
+        initial_guess,
+        method_enum,
+        tolerance,
+        max_iter,
+    ).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    Ok((
+        solution.into_pyarray(_py).to_owned().into(),
+        iterations as i32,
+        residual_norm,
+    ))
+}
+
+#[pyfunction]
+pub fn build_a_inverse_sparse(
+    _py: Python<'_>,
+    sire_ids: PyReadonlyArray1<i32>,
+    dam_ids: PyReadonlyArray1<i32>,
+) -> PyResult<(Py<PyAny>, Py<PyAny>, Py<PyAny>)> {
+    let sire_ids = sire_ids.as_slice()?;
+    let dam_ids = dam_ids.as_slice()?;
+
+    let sparse = crate::matrix::build_a_inverse_sparse(sire_ids, dam_ids);
+    let col_indices: Vec<i64> = sparse.col_indices.iter().map(|&c| c as i64).collect();
+    let row_ptr: Vec<i64> = sparse.row_ptr.iter().map(|&r| r as i64).collect();
+
+    Ok((
+        sparse.values.into_pyarray(_py).to_owned().into(),
+        col_indices.into_pyarray(_py).to_owned().into(),
+        row_ptr.into_pyarray(_py).to_owned().into(),
+    ))
+}
+
+#[pyfunction]
+pub fn blup_sparse(
+    _py: Python<'_>,
+    phenotypes: PyReadonlyArray1<f64>,
+    fixed_effects: PyReadonlyArray1<f64>,
+    random_effects: PyReadonlyArray1<f64>,
+    a_inv_values: PyReadonlyArray1<f64>,
+    a_inv_col_indices: Vec<usize>,
+    a_inv_row_ptr: Vec<usize>,
+    var_additive: f64,
+    var_residual: f64,
+    n: usize,
+    p: usize,
+    q: usize,
+) -> PyResult<(Py<PyAny>, Py<PyAny>)> {
+    let phenotypes = phenotypes.as_slice()?;
+    let fixed_effects = fixed_effects.as_slice()?;
+    let random_effects = random_effects.as_slice()?;
+
+    let a_inverse = crate::matrix::SparseMatrix {
+        values: a_inv_values.as_slice()?.to_vec(),
+        col_indices: a_inv_col_indices,
+        row_ptr: a_inv_row_ptr,
+        n_rows: q,
+        n_cols: q,
+    };
+
+    let result = fortran_ffi::blup_sparse(
+        phenotypes,
+        fixed_effects,
+        random_effects,
+        &a_inverse,
+        var_additive,
+        var_residual,
+        n,
+        p,
+        q,
+    ).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let beta = result.beta.into_pyarray(_py).to_owned();
+    let u = result.breeding_values.into_pyarray(_py).to_owned();
+
+    Ok((beta.into(), u.into()))
+}
+
+#[pyfunction]
+pub fn solve_mme_sparse(
+    _py: Python<'_>,
+    coef_values: PyReadonlyArray1<f64>,
+    coef_col_indices: Vec<usize>,
+    coef_row_ptr: Vec<usize>,
+    rhs: PyReadonlyArray1<f64>,
+    initial_guess: PyReadonlyArray1<f64>,
+    preconditioner: &str,
+    tolerance: f64,
+    max_iter: usize,
+) -> PyResult<(Py<PyAny>, i32)> {
+    let rhs = rhs.as_slice()?;
+    let initial_guess = initial_guess.as_slice()?;
+    let dim = rhs.len();
+
+    let coefficient_matrix = crate::matrix::SparseMatrix {
+        values: coef_values.as_slice()?.to_vec(),
+        col_indices: coef_col_indices,
+        row_ptr: coef_row_ptr,
+        n_rows: dim,
+        n_cols: dim,
+    };
+
+    let preconditioner_enum = match preconditioner {
+        "none" => fortran_ffi::SparsePreconditioner::None,
+        "jacobi" => fortran_ffi::SparsePreconditioner::Jacobi,
+        "incomplete_cholesky" => fortran_ffi::SparsePreconditioner::IncompleteCholesky,
+        _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid preconditioner")),
+    };
+
+    let (solution, iterations) = fortran_ffi::solve_mme_sparse(
+        &coefficient_matrix,
+        rhs,
+        initial_guess,
+        preconditioner_enum,
+        tolerance,
+        max_iter,
+    ).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    Ok((solution.into_pyarray(_py).to_owned().into(), iterations as i32))
+}
+
+#[pyfunction]
+pub fn simulate_population(
+    _py: Python<'_>,
+    marker_positions: PyReadonlyArray1<f64>,
+    chrom_marker_counts: Vec<usize>,
+    n_founders: usize,
+    n_individuals: usize,
+    generations: usize,
+    mating_scheme: &str,
+    crossover_interference: bool,
+    interference_shape: f64,
+    genotyping_error_rate: f64,
+    missing_rate: f64,
+    heritability: f64,
+    marker_effects: PyReadonlyArray1<f64>,
+    seed: u64,
+) -> PyResult<(Py<PyAny>, Py<PyAny>, Py<PyAny>, Py<PyAny>, Py<PyAny>, usize, usize)> {
+    let marker_positions = marker_positions.as_slice()?;
+    let marker_effects = marker_effects.as_slice()?.to_vec();
+
+    let mut chromosomes = Vec::with_capacity(chrom_marker_counts.len());
+    let mut offset = 0;
+    for &count in &chrom_marker_counts {
+        chromosomes.push(crate::simulate::ChromosomeMap {
+            marker_positions: marker_positions[offset..offset + count].to_vec(),
+        });
+        offset += count;
+    }
+
+    let mating_scheme_enum = match mating_scheme {
+        "selfing" => crate::simulate::MatingScheme::Selfing,
+        "sib_mating" => crate::simulate::MatingScheme::SibMating,
+        _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid mating_scheme")),
+    };
+
+    let config = crate::simulate::SimulationConfig {
+        chromosomes,
+        n_founders,
+        n_individuals,
+        generations,
+        mating_scheme: mating_scheme_enum,
+        crossover_interference,
+        interference_shape,
+        genotyping_error_rate,
+        missing_rate,
+        heritability,
+        marker_effects,
+        seed,
+    };
+
+    let result = crate::simulate::simulate_population(&config);
+
+    Ok((
+        result.observed_genotypes.into_pyarray(_py).to_owned().into(),
+        result.true_genotypes.into_pyarray(_py).to_owned().into(),
+        result.true_breeding_values.into_pyarray(_py).to_owned().into(),
+        result.phenotypes.into_pyarray(_py).to_owned().into(),
+        result.realized_relationship.into_pyarray(_py).to_owned().into(),
+        result.n_individuals,
+        result.n_markers,
+    ))
+}
+
+/// Elementwise add two NumPy arrays with broadcasting, operating directly on
+/// `numpy`'s `ArrayView` (itself backed by `ndarray`) so the input buffers
+/// are never copied into Rust - only the freshly-allocated result is.
+#[pyfunction]
+pub fn ndarray_broadcast_add(
+    _py: Python<'_>,
+    a: PyReadonlyArrayDyn<f32>,
+    b: PyReadonlyArrayDyn<f32>,
+) -> PyResult<Py<PyAny>> {
+    let a_view = a.as_array();
+    let b_view = b.as_array();
+
+    let shape = broadcast_shape(a_view.shape(), b_view.shape())
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("shapes are not broadcastable"))?;
+    let a_b = a_view.broadcast(IxDyn(&shape))
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("shapes are not broadcastable"))?;
+    let b_b = b_view.broadcast(IxDyn(&shape))
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("shapes are not broadcastable"))?;
+
+    let result = Zip::from(&a_b).and(&b_b).map_collect(|&x, &y| x + y);
+    Ok(result.into_pyarray(_py).to_owned().into())
+}
@@ -15,8 +15,7 @@ pub struct MotifMatch {
     pub match_str: String,
 }
 
-#[wasm_bindgen]
-pub fn needleman_wunsch(seq1: &str, seq2: &str, match_score: i32, mismatch_score: i32, gap_penalty: i32) -> JsValue {
+fn needleman_wunsch_core(seq1: &str, seq2: &str, match_score: i32, mismatch_score: i32, gap_penalty: i32) -> (i32, String, String) {
     let s1: Vec<char> = seq1.chars().collect();
     let s2: Vec<char> = seq2.chars().collect();
     let n = s1.len();
@@ -69,13 +68,17 @@ pub fn needleman_wunsch(seq1: &str, seq2: &str, match_score: i32, mismatch_score
         }
     }
 
-    let result = AlignmentResult {
-        score: score_matrix[n][m],
-        align1: align1.chars().rev().collect(),
-        align2: align2.chars().rev().collect(),
-    };
+    (
+        score_matrix[n][m],
+        align1.chars().rev().collect(),
+        align2.chars().rev().collect(),
+    )
+}
 
-    serde_wasm_bindgen::to_value(&result).unwrap()
+#[wasm_bindgen]
+pub fn needleman_wunsch(seq1: &str, seq2: &str, match_score: i32, mismatch_score: i32, gap_penalty: i32) -> JsValue {
+    let (score, align1, align2) = needleman_wunsch_core(seq1, seq2, match_score, mismatch_score, gap_penalty);
+    serde_wasm_bindgen::to_value(&AlignmentResult { score, align1, align2 }).unwrap()
 }
 
 #[wasm_bindgen]
@@ -140,6 +143,343 @@ pub fn smith_waterman(seq1: &str, seq2: &str, match_score: i32, mismatch_score:
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
+/// Sentinel for "unreachable" cells in the Gotoh affine-gap matrices; large
+/// enough that adding a gap/mismatch penalty can't overflow or wrap past 0.
+const GOTOH_NEG_INF: i32 = i32::MIN / 4;
+
+/// Which of the three Gotoh matrices a traceback cell belongs to: `M` ends in
+/// a match/mismatch, `Ix` ends in a gap in `seq2`, `Iy` ends in a gap in `seq1`.
+#[derive(Clone, Copy, PartialEq)]
+enum GotohState {
+    M,
+    Ix,
+    Iy,
+}
+
+fn needleman_wunsch_affine_core(
+    seq1: &str,
+    seq2: &str,
+    match_score: i32,
+    mismatch_score: i32,
+    gap_open: i32,
+    gap_extend: i32,
+) -> (i32, String, String) {
+    let s1: Vec<char> = seq1.chars().collect();
+    let s2: Vec<char> = seq2.chars().collect();
+    let n = s1.len();
+    let m = s2.len();
+
+    let mut mat = vec![vec![GOTOH_NEG_INF; m + 1]; n + 1];
+    let mut ix = vec![vec![GOTOH_NEG_INF; m + 1]; n + 1];
+    let mut iy = vec![vec![GOTOH_NEG_INF; m + 1]; n + 1];
+
+    mat[0][0] = 0;
+    for i in 1..=n {
+        ix[i][0] = -gap_open - (i as i32 - 1) * gap_extend;
+    }
+    for j in 1..=m {
+        iy[0][j] = -gap_open - (j as i32 - 1) * gap_extend;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let s = if s1[i - 1] == s2[j - 1] { match_score } else { mismatch_score };
+            mat[i][j] = mat[i - 1][j - 1].max(ix[i - 1][j - 1]).max(iy[i - 1][j - 1]) + s;
+            ix[i][j] = (mat[i - 1][j] - gap_open).max(ix[i - 1][j] - gap_extend);
+            iy[i][j] = (mat[i][j - 1] - gap_open).max(iy[i][j - 1] - gap_extend);
+        }
+    }
+
+    let final_score = mat[n][m].max(ix[n][m]).max(iy[n][m]);
+    let mut state = if final_score == mat[n][m] {
+        GotohState::M
+    } else if final_score == ix[n][m] {
+        GotohState::Ix
+    } else {
+        GotohState::Iy
+    };
+
+    let mut align1 = String::new();
+    let mut align2 = String::new();
+    let mut i = n;
+    let mut j = m;
+
+    while i > 0 || j > 0 {
+        match state {
+            GotohState::M => {
+                let s = if s1[i - 1] == s2[j - 1] { match_score } else { mismatch_score };
+                align1.push(s1[i - 1]);
+                align2.push(s2[j - 1]);
+                let prev = mat[i][j] - s;
+                state = if prev == mat[i - 1][j - 1] {
+                    GotohState::M
+                } else if prev == ix[i - 1][j - 1] {
+                    GotohState::Ix
+                } else {
+                    GotohState::Iy
+                };
+                i -= 1;
+                j -= 1;
+            }
+            GotohState::Ix => {
+                align1.push(s1[i - 1]);
+                align2.push('-');
+                state = if ix[i][j] == mat[i - 1][j] - gap_open {
+                    GotohState::M
+                } else {
+                    GotohState::Ix
+                };
+                i -= 1;
+            }
+            GotohState::Iy => {
+                align1.push('-');
+                align2.push(s2[j - 1]);
+                state = if iy[i][j] == mat[i][j - 1] - gap_open {
+                    GotohState::M
+                } else {
+                    GotohState::Iy
+                };
+                j -= 1;
+            }
+        }
+    }
+
+    (
+        final_score,
+        align1.chars().rev().collect(),
+        align2.chars().rev().collect(),
+    )
+}
+
+/// Global alignment with affine gap penalties (Gotoh's three-matrix recurrence),
+/// so long indels cost `gap_open + gap_extend * (length - 1)` instead of
+/// `gap_penalty * length`.
+#[wasm_bindgen]
+pub fn needleman_wunsch_affine(
+    seq1: &str,
+    seq2: &str,
+    match_score: i32,
+    mismatch_score: i32,
+    gap_open: i32,
+    gap_extend: i32,
+) -> JsValue {
+    let (score, align1, align2) =
+        needleman_wunsch_affine_core(seq1, seq2, match_score, mismatch_score, gap_open, gap_extend);
+    serde_wasm_bindgen::to_value(&AlignmentResult { score, align1, align2 }).unwrap()
+}
+
+fn smith_waterman_affine_core(
+    seq1: &str,
+    seq2: &str,
+    match_score: i32,
+    mismatch_score: i32,
+    gap_open: i32,
+    gap_extend: i32,
+) -> (i32, String, String) {
+    let s1: Vec<char> = seq1.chars().collect();
+    let s2: Vec<char> = seq2.chars().collect();
+    let n = s1.len();
+    let m = s2.len();
+
+    let mut mat = vec![vec![0; m + 1]; n + 1];
+    let mut ix = vec![vec![0; m + 1]; n + 1];
+    let mut iy = vec![vec![0; m + 1]; n + 1];
+
+    let mut max_score = 0;
+    let mut max_i = 0;
+    let mut max_j = 0;
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let s = if s1[i - 1] == s2[j - 1] { match_score } else { mismatch_score };
+            mat[i][j] = (mat[i - 1][j - 1].max(ix[i - 1][j - 1]).max(iy[i - 1][j - 1]) + s).max(0);
+            ix[i][j] = (mat[i - 1][j] - gap_open).max(ix[i - 1][j] - gap_extend).max(0);
+            iy[i][j] = (mat[i][j - 1] - gap_open).max(iy[i][j - 1] - gap_extend).max(0);
+
+            let best = mat[i][j].max(ix[i][j]).max(iy[i][j]);
+            if best > max_score {
+                max_score = best;
+                max_i = i;
+                max_j = j;
+            }
+        }
+    }
+
+    // Traceback from the best-scoring cell back down to a zero cell.
+    let mut align1 = String::new();
+    let mut align2 = String::new();
+    let mut i = max_i;
+    let mut j = max_j;
+    let mut state = if max_score == mat[i][j] {
+        GotohState::M
+    } else if max_score == ix[i][j] {
+        GotohState::Ix
+    } else {
+        GotohState::Iy
+    };
+
+    while i > 0 && j > 0 {
+        let current = match state {
+            GotohState::M => mat[i][j],
+            GotohState::Ix => ix[i][j],
+            GotohState::Iy => iy[i][j],
+        };
+        if current == 0 {
+            break;
+        }
+
+        match state {
+            GotohState::M => {
+                let s = if s1[i - 1] == s2[j - 1] { match_score } else { mismatch_score };
+                align1.push(s1[i - 1]);
+                align2.push(s2[j - 1]);
+                let prev = mat[i][j] - s;
+                state = if prev == mat[i - 1][j - 1] {
+                    GotohState::M
+                } else if prev == ix[i - 1][j - 1] {
+                    GotohState::Ix
+                } else {
+                    GotohState::Iy
+                };
+                i -= 1;
+                j -= 1;
+            }
+            GotohState::Ix => {
+                align1.push(s1[i - 1]);
+                align2.push('-');
+                state = if ix[i][j] == mat[i - 1][j] - gap_open {
+                    GotohState::M
+                } else {
+                    GotohState::Ix
+                };
+                i -= 1;
+            }
+            GotohState::Iy => {
+                align1.push('-');
+                align2.push(s2[j - 1]);
+                state = if iy[i][j] == mat[i][j - 1] - gap_open {
+                    GotohState::M
+                } else {
+                    GotohState::Iy
+                };
+                j -= 1;
+            }
+        }
+    }
+
+    (
+        max_score,
+        align1.chars().rev().collect(),
+        align2.chars().rev().collect(),
+    )
+}
+
+/// Local alignment with affine gap penalties (Gotoh's three-matrix recurrence),
+/// clamping every cell at 0 so alignments can restart anywhere.
+#[wasm_bindgen]
+pub fn smith_waterman_affine(
+    seq1: &str,
+    seq2: &str,
+    match_score: i32,
+    mismatch_score: i32,
+    gap_open: i32,
+    gap_extend: i32,
+) -> JsValue {
+    let (score, align1, align2) =
+        smith_waterman_affine_core(seq1, seq2, match_score, mismatch_score, gap_open, gap_extend);
+    serde_wasm_bindgen::to_value(&AlignmentResult { score, align1, align2 }).unwrap()
+}
+
+/// A single PSSM scan hit, reporting both the raw log-odds score and a
+/// score normalized to [0, 1] against the matrix's theoretical min/max.
+#[derive(Serialize, Deserialize)]
+pub struct PssmMatch {
+    pub start: usize,
+    pub end: usize,
+    pub score: f32,
+    pub normalized_score: f32,
+}
+
+fn base_index(c: char) -> Option<usize> {
+    match c.to_ascii_uppercase() {
+        'A' => Some(0),
+        'C' => Some(1),
+        'G' => Some(2),
+        'T' => Some(3),
+        _ => None,
+    }
+}
+
+/// Build a position-specific scoring matrix (log-odds, base order A,C,G,T) from
+/// observed base counts per position. `counts` is a flat `[position][base]`
+/// slice of length `length * 4`; `background` holds the genome-wide base
+/// frequencies (A,C,G,T) the counts are scored against, and `pseudocount` keeps
+/// unobserved bases from producing `-inf` log-odds.
+#[wasm_bindgen]
+pub fn pssm_from_counts(counts: &[f32], length: usize, background: &[f32], pseudocount: f32) -> Vec<f32> {
+    let mut pssm = vec![0.0f32; length * 4];
+
+    for pos in 0..length {
+        let row = &counts[pos * 4..pos * 4 + 4];
+        let total: f32 = row.iter().sum();
+
+        for base in 0..4 {
+            let freq = (row[base] + pseudocount) / (total + 4.0 * pseudocount);
+            let bg = background[base].max(1e-9);
+            pssm[pos * 4 + base] = (freq / bg).log2();
+        }
+    }
+
+    pssm
+}
+
+/// Slide a PSSM (flat `[position][base]` slice, length `length * 4`) across
+/// `genome` and report every window scoring at or above `threshold`. Bases
+/// outside A/C/G/T (e.g. `N`) contribute that position's minimum score, so
+/// an ambiguous base never scores higher than the worst real base there.
+#[wasm_bindgen]
+pub fn scan_pssm(genome: &str, pssm: &[f32], length: usize, threshold: f32) -> JsValue {
+    let genome_chars: Vec<char> = genome.chars().collect();
+    let mut matches = Vec::new();
+
+    if length == 0 || genome_chars.len() < length {
+        return serde_wasm_bindgen::to_value(&matches).unwrap();
+    }
+
+    // Sum of the per-position min/max so each hit's score can be normalized to [0, 1].
+    let mut min_score = 0.0f32;
+    let mut max_score = 0.0f32;
+    for pos in 0..length {
+        let row = &pssm[pos * 4..pos * 4 + 4];
+        min_score += row.iter().cloned().fold(f32::INFINITY, f32::min);
+        max_score += row.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    }
+    let range = max_score - min_score;
+
+    for start in 0..=(genome_chars.len() - length) {
+        let mut score = 0.0f32;
+        for (pos, &ch) in genome_chars[start..start + length].iter().enumerate() {
+            let row = &pssm[pos * 4..pos * 4 + 4];
+            score += match base_index(ch) {
+                Some(base) => row[base],
+                None => row.iter().cloned().fold(f32::INFINITY, f32::min),
+            };
+        }
+
+        if score >= threshold {
+            let normalized_score = if range > 0.0 { (score - min_score) / range } else { 0.0 };
+            matches.push(PssmMatch {
+                start,
+                end: start + length,
+                score,
+                normalized_score,
+            });
+        }
+    }
+
+    serde_wasm_bindgen::to_value(&matches).unwrap()
+}
+
 #[wasm_bindgen]
 pub fn search_motif(genome: &str, motif: &str) -> JsValue {
     let mut matches = Vec::new();
@@ -185,3 +525,224 @@ pub fn search_motif(genome: &str, motif: &str) -> JsValue {
 
     serde_wasm_bindgen::to_value(&matches).unwrap()
 }
+
+/// A single parsed FASTA record.
+#[derive(Serialize, Deserialize)]
+pub struct FastaRecord {
+    pub id: String,
+    pub description: String,
+    pub sequence: String,
+}
+
+/// A single parsed FASTQ record, with the raw quality string decoded to
+/// Phred+33 scores.
+#[derive(Serialize, Deserialize)]
+pub struct FastqRecord {
+    pub id: String,
+    pub description: String,
+    pub sequence: String,
+    pub quality: String,
+    pub phred_scores: Vec<u8>,
+}
+
+/// A malformed record or line encountered while parsing, reported instead
+/// of panicking.
+#[derive(Serialize, Deserialize)]
+pub struct RecordParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FastaParseResult {
+    pub records: Vec<FastaRecord>,
+    pub errors: Vec<RecordParseError>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FastqParseResult {
+    pub records: Vec<FastqRecord>,
+    pub errors: Vec<RecordParseError>,
+}
+
+/// Split a FASTA/FASTQ header (without its `>`/`@` prefix) into the id
+/// (first whitespace-delimited token) and the remaining description.
+fn split_header(header: &str) -> (String, String) {
+    match header.find(char::is_whitespace) {
+        Some(idx) => (header[..idx].to_string(), header[idx..].trim_start().to_string()),
+        None => (header.to_string(), String::new()),
+    }
+}
+
+fn parse_fasta_text(text: &str) -> (Vec<FastaRecord>, Vec<RecordParseError>) {
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    let mut current_id: Option<String> = None;
+    let mut current_desc = String::new();
+    let mut current_seq = String::new();
+
+    for (line_no, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('>') {
+            if let Some(id) = current_id.take() {
+                records.push(FastaRecord {
+                    id,
+                    description: current_desc.clone(),
+                    sequence: current_seq.clone(),
+                });
+            }
+            let (id, description) = split_header(header);
+            current_id = Some(id);
+            current_desc = description;
+            current_seq = String::new();
+        } else if current_id.is_some() {
+            current_seq.push_str(line.trim());
+        } else {
+            errors.push(RecordParseError {
+                line: line_no + 1,
+                message: "sequence data before a header line".to_string(),
+            });
+        }
+    }
+
+    if let Some(id) = current_id {
+        records.push(FastaRecord {
+            id,
+            description: current_desc,
+            sequence: current_seq,
+        });
+    }
+
+    (records, errors)
+}
+
+/// Parse multi-record, possibly multi-line FASTA text into records, collecting
+/// malformed lines as errors instead of panicking.
+#[wasm_bindgen]
+pub fn parse_fasta(text: &str) -> JsValue {
+    let (records, errors) = parse_fasta_text(text);
+    serde_wasm_bindgen::to_value(&FastaParseResult { records, errors }).unwrap()
+}
+
+/// Decode a Phred+33 (Sanger) quality string to per-base error-probability scores.
+fn phred_scores(quality: &str) -> Vec<u8> {
+    quality.bytes().map(|b| b.saturating_sub(33)).collect()
+}
+
+fn parse_fastq_text(text: &str) -> (Vec<FastqRecord>, Vec<RecordParseError>) {
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let header = lines[i].trim_end();
+        if header.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        let line_no = i + 1;
+        if !header.starts_with('@') {
+            errors.push(RecordParseError {
+                line: line_no,
+                message: "expected a '@' header line".to_string(),
+            });
+            i += 1;
+            continue;
+        }
+        if i + 3 >= lines.len() {
+            errors.push(RecordParseError {
+                line: line_no,
+                message: "truncated FASTQ record".to_string(),
+            });
+            break;
+        }
+
+        let sequence = lines[i + 1].trim_end().to_string();
+        let separator = lines[i + 2].trim_end();
+        let quality = lines[i + 3].trim_end().to_string();
+
+        if !separator.starts_with('+') {
+            errors.push(RecordParseError {
+                line: line_no + 2,
+                message: "expected a '+' separator line".to_string(),
+            });
+            i += 4;
+            continue;
+        }
+        if quality.len() != sequence.len() {
+            errors.push(RecordParseError {
+                line: line_no + 3,
+                message: "quality string length does not match sequence length".to_string(),
+            });
+            i += 4;
+            continue;
+        }
+
+        let (id, description) = split_header(&header[1..]);
+        records.push(FastqRecord {
+            id,
+            description,
+            phred_scores: phred_scores(&quality),
+            sequence,
+            quality,
+        });
+
+        i += 4;
+    }
+
+    (records, errors)
+}
+
+/// Parse FASTQ text (4-line records) into records with decoded Phred scores,
+/// collecting malformed records as errors instead of panicking.
+#[wasm_bindgen]
+pub fn parse_fastq(text: &str) -> JsValue {
+    let (records, errors) = parse_fastq_text(text);
+    serde_wasm_bindgen::to_value(&FastqParseResult { records, errors }).unwrap()
+}
+
+/// A pairwise alignment between two FASTA records.
+#[derive(Serialize, Deserialize)]
+pub struct PairwiseAlignment {
+    pub id1: String,
+    pub id2: String,
+    pub score: i32,
+    pub align1: String,
+    pub align2: String,
+}
+
+/// Parse `fasta_text` and run Needleman-Wunsch alignment over every pair of
+/// records, so a FASTA file can be loaded and aligned pairwise in one call
+/// instead of parsing and aligning as two separate round-trips.
+#[wasm_bindgen]
+pub fn align_records(fasta_text: &str, match_score: i32, mismatch_score: i32, gap_penalty: i32) -> JsValue {
+    let (records, _errors) = parse_fasta_text(fasta_text);
+    let mut alignments = Vec::new();
+
+    for i in 0..records.len() {
+        for j in (i + 1)..records.len() {
+            let (score, align1, align2) = needleman_wunsch_core(
+                &records[i].sequence,
+                &records[j].sequence,
+                match_score,
+                mismatch_score,
+                gap_penalty,
+            );
+            alignments.push(PairwiseAlignment {
+                id1: records[i].id.clone(),
+                id2: records[j].id.clone(),
+                score,
+                align1,
+                align2,
+            });
+        }
+    }
+
+    serde_wasm_bindgen::to_value(&alignments).unwrap()
+}
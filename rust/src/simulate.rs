@@ -0,0 +1,436 @@
+//! Genotype/pedigree simulator for end-to-end validation and power analysis.
+//!
+//! Generates marker genotype matrices (coded 0/1/2, matching
+//! [`crate::fortran_ffi::compute_grm`]/[`crate::fortran_ffi::gblup`]) for a
+//! population descending from a handful of founder strains, together with
+//! true breeding values, a realized additive relationship matrix, and
+//! simulated phenotypes — so callers can run their own GEBVs through the
+//! real pipeline and correlate them against ground truth.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::matrix::gaussian_sample;
+
+/// How descendants of the founder strains are mated across generations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatingScheme {
+    /// Each individual is selfed to produce its offspring (only sensible
+    /// for self-fertilizing species; simplest way to fix inbred lines).
+    Selfing,
+    /// Individuals are mated with a full sibling each generation.
+    SibMating,
+}
+
+/// One simulated chromosome: marker positions along the genetic map, in
+/// Morgans, sorted ascending.
+#[derive(Debug, Clone)]
+pub struct ChromosomeMap {
+    pub marker_positions: Vec<f64>,
+}
+
+/// Configuration for [`simulate_population`].
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Genetic map, one entry per chromosome.
+    pub chromosomes: Vec<ChromosomeMap>,
+    /// Number of founder strains (each founder is fully homozygous, i.e. an
+    /// inbred line, at a distinct allele state per marker).
+    pub n_founders: usize,
+    /// Number of descendants simulated in the final generation.
+    pub n_individuals: usize,
+    /// Number of generations of mating since the founder cross.
+    pub generations: usize,
+    /// Mating scheme applied every generation after the initial cross.
+    pub mating_scheme: MatingScheme,
+    /// When `true`, crossover positions are drawn with interference (a
+    /// Gamma-distributed inter-crossover distance instead of a plain
+    /// Poisson process); see [`interference_shape`](Self::interference_shape).
+    pub crossover_interference: bool,
+    /// Shape parameter `ν` of the Gamma inter-crossover-distance model used
+    /// when `crossover_interference` is set (ignored otherwise). Larger
+    /// values mean stronger interference (crossovers spaced more evenly);
+    /// `ν = 1` recovers a plain Poisson process.
+    pub interference_shape: f64,
+    /// Genotyping error rate: probability an observed allele call is
+    /// flipped to a different homozygote/heterozygote state.
+    pub genotyping_error_rate: f64,
+    /// Fraction of observed genotype calls replaced with missing (`-1`).
+    pub missing_rate: f64,
+    /// Narrow-sense heritability used to draw phenotypes from the true
+    /// breeding values.
+    pub heritability: f64,
+    /// Per-marker additive effect used to compute true breeding values
+    /// (length must equal the total number of markers across all
+    /// chromosomes).
+    pub marker_effects: Vec<f64>,
+    /// Seed for the reproducible RNG.
+    pub seed: u64,
+}
+
+/// Output of [`simulate_population`].
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    /// Observed genotypes (n x m, row-major), with genotyping error and
+    /// missing data applied (`-1` = missing, matching
+    /// [`crate::genomics`]'s convention).
+    pub observed_genotypes: Vec<i32>,
+    /// True genotypes (n x m, row-major), before error/missing are applied.
+    pub true_genotypes: Vec<i32>,
+    /// True breeding value of each individual (sum of marker effects
+    /// weighted by true allele dosage).
+    pub true_breeding_values: Vec<f64>,
+    /// Phenotype of each individual, drawn as `breeding_value + residual`
+    /// with residual variance set from `heritability`.
+    pub phenotypes: Vec<f64>,
+    /// Realized additive relationship matrix (n x n, row-major), estimated
+    /// from identity-by-descent tracked through the simulated meioses
+    /// rather than from the observed marker genotypes.
+    pub realized_relationship: Vec<f64>,
+    pub n_individuals: usize,
+    pub n_markers: usize,
+}
+
+/// One simulated individual: per-chromosome haplotype pairs, each haplotype
+/// recording which founder strain each marker descends from (for tracking
+/// realized IBD); allele dosage is derived from these founder labels when
+/// the genotype matrices are built.
+struct SimIndividual {
+    /// `ancestry[c][h][m]` = founder index that marker `m` of chromosome
+    /// `c`, haplotype `h` (0 or 1), descends from.
+    ancestry: Vec<[Vec<usize>; 2]>,
+}
+
+fn founder(chromosomes: &[ChromosomeMap], founder_id: usize) -> SimIndividual {
+    let ancestry = chromosomes
+        .iter()
+        .map(|c| {
+            let n_markers = c.marker_positions.len();
+            [vec![founder_id; n_markers], vec![founder_id; n_markers]]
+        })
+        .collect();
+    SimIndividual { ancestry }
+}
+
+/// Draw a Gamma(`shape`, 1) variate via Marsaglia & Tsang (2000); boosts
+/// `shape < 1` via the standard `U^(1/shape)` trick so every shape is
+/// supported.
+fn gamma_sample(shape: f64, rng: &mut impl Rng) -> f64 {
+    if shape < 1.0 {
+        let u: f64 = rng.gen::<f64>().max(1e-12);
+        return gamma_sample(shape + 1.0, rng) * u.powf(1.0 / shape);
+    }
+
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let mut x;
+        let mut v;
+        loop {
+            x = gaussian_sample(rng);
+            v = 1.0 + c * x;
+            if v > 0.0 {
+                break;
+            }
+        }
+        v = v * v * v;
+        let u: f64 = rng.gen();
+        if u < 1.0 - 0.0331 * x.powi(4) || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+/// Crossover positions (in Morgans) along a chromosome of length
+/// `map_length`, either a plain Poisson process (`interference = false`) or
+/// a Gamma-renewal process with shape `interference_shape` (`> 1`
+/// strengthens interference; `1.0` recovers the Poisson process).
+fn crossover_positions(
+    map_length: f64,
+    interference: bool,
+    interference_shape: f64,
+    rng: &mut impl Rng,
+) -> Vec<f64> {
+    let mut positions = Vec::new();
+    let mut pos = 0.0;
+    loop {
+        let gap = if interference {
+            gamma_sample(interference_shape.max(1e-3), rng) / interference_shape.max(1e-3)
+        } else {
+            -rng.gen::<f64>().max(1e-12).ln()
+        };
+        pos += gap;
+        if pos >= map_length {
+            break;
+        }
+        positions.push(pos);
+    }
+    positions
+}
+
+/// Form a gamete from a parent via recombination along its real genetic
+/// map, returning the founder-ancestry track for each chromosome.
+fn make_gamete(
+    parent: &SimIndividual,
+    chromosomes: &[ChromosomeMap],
+    interference: bool,
+    interference_shape: f64,
+    rng: &mut impl Rng,
+) -> Vec<Vec<usize>> {
+    chromosomes
+        .iter()
+        .enumerate()
+        .map(|(c, map)| {
+            let n_markers = map.marker_positions.len();
+            let map_length = map.marker_positions.last().copied().unwrap_or(0.0).max(1e-9);
+            let breakpoints =
+                crossover_positions(map_length, interference, interference_shape, rng);
+
+            let mut gamete = vec![0usize; n_markers];
+            let mut from_hap = rng.gen::<bool>() as usize;
+            let mut next_break = 0;
+            for m in 0..n_markers {
+                while next_break < breakpoints.len()
+                    && breakpoints[next_break] <= map.marker_positions[m]
+                {
+                    from_hap = 1 - from_hap;
+                    next_break += 1;
+                }
+                gamete[m] = parent.ancestry[c][from_hap][m];
+            }
+            gamete
+        })
+        .collect()
+}
+
+fn mate(
+    dam: &SimIndividual,
+    sire: &SimIndividual,
+    chromosomes: &[ChromosomeMap],
+    interference: bool,
+    interference_shape: f64,
+    rng: &mut impl Rng,
+) -> SimIndividual {
+    let from_dam = make_gamete(dam, chromosomes, interference, interference_shape, rng);
+    let from_sire = make_gamete(sire, chromosomes, interference, interference_shape, rng);
+    let ancestry = from_dam
+        .into_iter()
+        .zip(from_sire)
+        .map(|(d, s)| [d, s])
+        .collect();
+    SimIndividual { ancestry }
+}
+
+/// Realized additive relationship between two individuals: twice the
+/// average, over all marker x haplotype-pair combinations, of the
+/// probability the two drawn haplotypes are identical by descent (same
+/// founder origin).
+fn ibd_relationship(a: &SimIndividual, b: &SimIndividual, same: bool) -> f64 {
+    let mut ibd_sum = 0.0;
+    let mut count = 0usize;
+    for c in 0..a.ancestry.len() {
+        let n_markers = a.ancestry[c][0].len();
+        for m in 0..n_markers {
+            for ha in 0..2 {
+                for hb in 0..2 {
+                    if same && ha == hb {
+                        continue;
+                    }
+                    if a.ancestry[c][ha][m] == b.ancestry[c][hb][m] {
+                        ibd_sum += 1.0;
+                    }
+                    count += 1;
+                }
+            }
+        }
+    }
+    if count == 0 {
+        return if same { 1.0 } else { 0.0 };
+    }
+    let kinship = ibd_sum / count as f64;
+    2.0 * kinship
+}
+
+/// Simulate a marker genotype matrix, true breeding values, a realized
+/// additive relationship matrix, and phenotypes for a population descending
+/// from `config.n_founders` founder strains through `config.generations` of
+/// `config.mating_scheme` mating.
+pub fn simulate_population(config: &SimulationConfig) -> SimulationResult {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let n_markers: usize = config
+        .chromosomes
+        .iter()
+        .map(|c| c.marker_positions.len())
+        .sum();
+    let n = config.n_individuals;
+
+    // Founders: distinct fully-homozygous strains, assigned round-robin as
+    // parents of the initial cross.
+    let founders: Vec<SimIndividual> = (0..config.n_founders.max(1))
+        .map(|f| founder(&config.chromosomes, f))
+        .collect();
+
+    let mut population: Vec<SimIndividual> = (0..n)
+        .map(|i| {
+            let dam = &founders[i % founders.len()];
+            let sire = &founders[(i + 1) % founders.len()];
+            mate(
+                dam,
+                sire,
+                &config.chromosomes,
+                config.crossover_interference,
+                config.interference_shape,
+                &mut rng,
+            )
+        })
+        .collect();
+
+    for _ in 0..config.generations {
+        population = (0..n)
+            .map(|i| match config.mating_scheme {
+                MatingScheme::Selfing => mate(
+                    &population[i],
+                    &population[i],
+                    &config.chromosomes,
+                    config.crossover_interference,
+                    config.interference_shape,
+                    &mut rng,
+                ),
+                MatingScheme::SibMating => {
+                    let partner = &population[(i + 1) % n];
+                    mate(
+                        &population[i],
+                        partner,
+                        &config.chromosomes,
+                        config.crossover_interference,
+                        config.interference_shape,
+                        &mut rng,
+                    )
+                }
+            })
+            .collect();
+    }
+
+    // True and observed genotype dosage (0/1/2 copies of founder-strain-0's
+    // allele at each marker; any other founder's allele is treated as the
+    // alternate allele, a biallelic simplification consistent with the
+    // 0/1/2 dosage coding the rest of this crate uses).
+    let mut true_genotypes = vec![0i32; n * n_markers];
+    let mut observed_genotypes = vec![0i32; n * n_markers];
+    for i in 0..n {
+        let mut m_idx = 0;
+        for c in 0..config.chromosomes.len() {
+            let n_markers_c = config.chromosomes[c].marker_positions.len();
+            for m in 0..n_markers_c {
+                let dosage = (population[i].ancestry[c][0][m] == 0) as i32
+                    + (population[i].ancestry[c][1][m] == 0) as i32;
+                true_genotypes[i * n_markers + m_idx] = dosage;
+
+                let mut observed = dosage;
+                if rng.gen::<f64>() < config.genotyping_error_rate {
+                    observed = match observed {
+                        0 => 1 + (rng.gen::<bool>() as i32),
+                        2 => 1 - (rng.gen::<bool>() as i32),
+                        _ => if rng.gen::<bool>() { 0 } else { 2 },
+                    };
+                }
+                if rng.gen::<f64>() < config.missing_rate {
+                    observed = -1;
+                }
+                observed_genotypes[i * n_markers + m_idx] = observed;
+                m_idx += 1;
+            }
+        }
+    }
+
+    let true_breeding_values: Vec<f64> = (0..n)
+        .map(|i| {
+            (0..n_markers)
+                .map(|m| {
+                    config.marker_effects.get(m).copied().unwrap_or(0.0)
+                        * true_genotypes[i * n_markers + m] as f64
+                })
+                .sum()
+        })
+        .collect();
+
+    let tbv_var = {
+        let mean = true_breeding_values.iter().sum::<f64>() / n as f64;
+        true_breeding_values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64
+    };
+    let residual_sd = if config.heritability > 0.0 && config.heritability < 1.0 {
+        (tbv_var * (1.0 - config.heritability) / config.heritability).sqrt()
+    } else {
+        0.0
+    };
+    let phenotypes: Vec<f64> = true_breeding_values
+        .iter()
+        .map(|&tbv| tbv + residual_sd * gaussian_sample(&mut rng))
+        .collect();
+
+    let mut realized_relationship = vec![0.0; n * n];
+    for i in 0..n {
+        realized_relationship[i * n + i] =
+            1.0 + ibd_relationship(&population[i], &population[i], true) / 2.0;
+        for j in (i + 1)..n {
+            let r = ibd_relationship(&population[i], &population[j], false);
+            realized_relationship[i * n + j] = r;
+            realized_relationship[j * n + i] = r;
+        }
+    }
+
+    SimulationResult {
+        observed_genotypes,
+        true_genotypes,
+        true_breeding_values,
+        phenotypes,
+        realized_relationship,
+        n_individuals: n,
+        n_markers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_founder_collapses_to_deterministic_output() {
+        // With n_founders = 1, every haplotype in every generation descends
+        // from founder 0 regardless of recombination/mating, so dosage,
+        // true breeding value, realized relationship, and (since the
+        // across-individual variance is then exactly zero) phenotype are
+        // all deterministic despite the simulator's internal RNG use.
+        let config = SimulationConfig {
+            chromosomes: vec![ChromosomeMap { marker_positions: vec![0.0, 1.0] }],
+            n_founders: 1,
+            n_individuals: 2,
+            generations: 1,
+            mating_scheme: MatingScheme::Selfing,
+            crossover_interference: false,
+            interference_shape: 1.0,
+            genotyping_error_rate: 0.0,
+            missing_rate: 0.0,
+            heritability: 0.5,
+            marker_effects: vec![1.0, 2.0],
+            seed: 7,
+        };
+
+        let result = simulate_population(&config);
+
+        assert_eq!(result.n_individuals, 2);
+        assert_eq!(result.n_markers, 2);
+        assert_eq!(result.true_genotypes, vec![2, 2, 2, 2]);
+        assert_eq!(result.observed_genotypes, vec![2, 2, 2, 2]);
+        // true_breeding_value = 1*2 + 2*2 = 6.0 for both individuals.
+        assert_eq!(result.true_breeding_values, vec![6.0, 6.0]);
+        // Zero across-individual variance in true_breeding_values makes the
+        // residual_sd exactly 0.0, so phenotype == true_breeding_value.
+        assert_eq!(result.phenotypes, vec![6.0, 6.0]);
+        // Every haplotype is IBD with every other (same single founder), so
+        // the realized relationship matrix is a constant 2.0 throughout.
+        for &r in &result.realized_relationship {
+            assert!((r - 2.0).abs() < 1e-9);
+        }
+    }
+}
@@ -102,3 +102,521 @@ fn test_selection_index() {
     let result = calculate_selection_index(&traits, &weights, 3, 2);
     assert!(!result.is_null());
 }
+
+#[wasm_bindgen_test]
+fn test_scan_pssm_single_position_best_match_is_normalized_to_one() {
+    // A single-position PSSM built from an all-A count column: the best
+    // (A) base gets the matrix's max score, so a genome window that's a
+    // perfect match normalizes to exactly 1.0.
+    let counts = vec![10.0, 0.0, 0.0, 0.0];
+    let background = vec![0.25, 0.25, 0.25, 0.25];
+    let pssm = pssm_from_counts(&counts, 1, &background, 1.0);
+
+    let result = scan_pssm("A", &pssm, 1, f32::NEG_INFINITY);
+    let matches: Vec<PssmMatch> = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].start, 0);
+    assert_eq!(matches[0].end, 1);
+    assert!((matches[0].normalized_score - 1.0).abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+fn test_allele_frequencies_gl_converges_to_hard_calls() {
+    // 4 samples, 1 marker, fully-informative (one-hot) likelihoods encoding
+    // hard calls AA, AB, AB, BB -> true allele freq 4/8 = 0.5, recovered in
+    // a single EM iteration since there's no ambiguity to resolve.
+    let likelihoods = vec![
+        1.0, 0.0, 0.0, // sample0: AA
+        0.0, 1.0, 0.0, // sample1: AB
+        0.0, 1.0, 0.0, // sample2: AB
+        0.0, 0.0, 1.0, // sample3: BB
+    ];
+    let result = calculate_allele_frequencies_gl(&likelihoods, 4, 1);
+    let parsed: AlleleFrequenciesGL = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.allele_freq[0] - 0.5).abs() < 1e-9);
+    assert_eq!(parsed.dosages, vec![2.0, 1.0, 1.0, 0.0]);
+    assert_eq!(parsed.iterations[0], 1);
+}
+
+#[wasm_bindgen_test]
+fn test_pcrelate_no_pcs_reduces_to_population_allele_freq() {
+    // 2 samples, 2 markers, 0 PCs: the per-individual allele frequency
+    // regresses onto an intercept-only design, so it reduces to the plain
+    // marker mean and the resulting kinship/inbreeding is hand-derivable.
+    let genotypes = vec![0, 2, 2, 0]; // sample0: [0,2], sample1: [2,0]
+    let pcs: Vec<f64> = vec![];
+    let result = pcrelate(&genotypes, 2, 2, &pcs, 0);
+    let parsed: PcRelateResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.kinship[0] - 1.0).abs() < 1e-9);
+    assert!((parsed.kinship[1] - (-1.0)).abs() < 1e-9);
+    assert!((parsed.kinship[2] - (-1.0)).abs() < 1e-9);
+    assert!((parsed.kinship[3] - 1.0).abs() < 1e-9);
+    assert!((parsed.inbreeding[0] - 1.0).abs() < 1e-9);
+    assert!((parsed.inbreeding[1] - 1.0).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_gwas_mlm_identity_grm_matches_ols() {
+    // With an identity GRM (no relatedness), the eigen-rotation is the
+    // identity and the GLS fit reduces to OLS regardless of the fitted
+    // delta, so a perfectly linear genotype/phenotype relationship recovers
+    // the exact OLS slope with zero residual variance.
+    let genotypes = vec![0.0, 1.0, 2.0, 1.0];
+    let phenotypes = vec![2.0, 4.0, 6.0, 4.0]; // y = 2 + 2*genotype, exactly
+    let grm = vec![
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    ];
+    let result = gwas_mlm(&genotypes, &phenotypes, 4, 1, &grm, true);
+    let parsed: GwasMlmResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.associations[0].beta - 2.0).abs() < 1e-6);
+    assert!(parsed.associations[0].se.abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+fn test_hmm_clean_genotypes_single_marker_no_error_is_exact() {
+    // Single marker, zero error rate: the HMM has no transitions to
+    // integrate over and perfect emissions, so it must decode back the
+    // observed genotype exactly with a one-hot posterior.
+    let genotypes = vec![1]; // AB
+    let positions_cm = vec![0.0];
+    let result = hmm_clean_genotypes(&genotypes, 1, 1, &positions_cm, 0.01, 0.0);
+    let parsed: HmmCleanResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.genotypes, vec![1]);
+    assert!(parsed.posteriors[0].abs() < 1e-9);
+    assert!((parsed.posteriors[1] - 1.0).abs() < 1e-9);
+    assert!(parsed.posteriors[2].abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_eigen_decompose_diagonal_matrix() {
+    // A diagonal matrix's eigenvalues/eigenvectors are its diagonal entries
+    // and the standard basis, sorted descending - Jacobi should leave it
+    // untouched (no off-diagonal to rotate away).
+    let matrix = vec![3.0, 0.0, 0.0, 1.0];
+    let result = eigen_decompose(&matrix, 2, 2);
+    let parsed: EigenDecomposeResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.eigenvalues[0] - 3.0).abs() < 1e-8);
+    assert!((parsed.eigenvalues[1] - 1.0).abs() < 1e-8);
+    assert_eq!(parsed.eigenvectors.len(), 4);
+    // Signs are arbitrary for Jacobi eigenvectors, so compare magnitudes.
+    assert!((parsed.eigenvectors[0].abs() - 1.0).abs() < 1e-8);
+    assert!(parsed.eigenvectors[1].abs() < 1e-8);
+    assert!(parsed.eigenvectors[2].abs() < 1e-8);
+    assert!((parsed.eigenvectors[3].abs() - 1.0).abs() < 1e-8);
+    assert!((parsed.explained_variance[0] - 75.0).abs() < 1e-6);
+    assert!((parsed.cumulative_variance[1] - 100.0).abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_ld_pair_em_complete_coupling_phase() {
+    // No double heterozygotes, so phase is unambiguous: haplotype freqs,
+    // D, D', and r^2 are all exactly computable from the genotype counts.
+    let geno1 = vec![2, 0, 2, 0];
+    let geno2 = vec![2, 0, 2, 0];
+    let result = calculate_ld_pair_em(&geno1, &geno2);
+    let parsed: LDResultEM = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.haplotype_freqs[0] - 0.5).abs() < 1e-9); // AB
+    assert!(parsed.haplotype_freqs[1].abs() < 1e-9); // Ab
+    assert!(parsed.haplotype_freqs[2].abs() < 1e-9); // aB
+    assert!((parsed.haplotype_freqs[3] - 0.5).abs() < 1e-9); // ab
+    assert!((parsed.d - 0.25).abs() < 1e-9);
+    assert!((parsed.d_prime - 1.0).abs() < 1e-9);
+    assert!((parsed.r_squared - 1.0).abs() < 1e-9);
+    assert_eq!(parsed.iterations, 2);
+}
+
+#[wasm_bindgen_test]
+fn test_estimate_heritability_reml_normal_equations_are_self_consistent() {
+    // GRM = identity, 1 fixed effect (intercept). Rather than hand-tracing
+    // the full EM-REML iteration (log-determinants, traces of C^-1 G^-1),
+    // independently reconstruct the final mixed-model coefficient matrix
+    // from the *returned* variance components and check it actually solves
+    // the normal equations C @ [b; u] = [X'y; y] - i.e. the EM loop landed
+    // on a fixed point of its own closed-form MME solve.
+    let phenotypes = vec![5.0, 7.0, 6.0];
+    let fixed_design = vec![1.0, 1.0, 1.0];
+    let grm = vec![
+        1.0, 0.0, 0.0,
+        0.0, 1.0, 0.0,
+        0.0, 0.0, 1.0,
+    ];
+    let result = estimate_heritability_reml(&phenotypes, &fixed_design, 1, &grm, 3, 100, 1e-8);
+    let parsed: RemlResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!(parsed.converged);
+    assert!((parsed.heritability - parsed.var_genetic / (parsed.var_genetic + parsed.var_residual)).abs() < 1e-9);
+
+    let lambda = parsed.var_residual / parsed.var_genetic;
+    let b = parsed.fixed_effects[0];
+    let u = &parsed.breeding_values;
+
+    // Row 0: X'X*b + sum(u) = X'y
+    let xty: f64 = phenotypes.iter().sum();
+    let row0 = 3.0 * b + u.iter().sum::<f64>();
+    assert!((row0 - xty).abs() < 1e-4);
+
+    // Row (1+i): b + (1 + lambda)*u[i] = y[i]  (G^-1 = I)
+    for i in 0..3 {
+        let row = b + (1.0 + lambda) * u[i];
+        assert!((row - phenotypes[i]).abs() < 1e-4);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_estimate_ssgblup_full_genotyping_collapses_h_inverse_to_grm_inverse() {
+    // Unrelated pedigree (A = I) and every individual genotyped with
+    // blend_weight = 1.0: H^-1's pedigree and A22 terms cancel exactly,
+    // leaving H^-1 = G^-1, so the coefficient matrix I + lambda*H^-1 and
+    // its Gauss-Seidel solution are hand-derivable from the 2x2 GRM alone.
+    let phenotypes = vec![10.0, 12.0];
+    let pedigree_a = vec![
+        1.0, 0.0,
+        0.0, 1.0,
+    ];
+    let grm = vec![
+        1.0, 0.5,
+        0.5, 1.0,
+    ];
+    let genotyped_idx = vec![0, 1];
+    let result = estimate_ssgblup(&phenotypes, &pedigree_a, &grm, &genotyped_idx, 2, 0.5, 1.0);
+    let parsed: SSGBLUPResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.mean - 11.0).abs() < 1e-9);
+    assert!((parsed.genetic_variance - 1.0).abs() < 1e-9);
+    assert!((parsed.residual_variance - 1.0).abs() < 1e-9);
+
+    // H^-1 = G^-1 = [[4/3, -2/3], [-2/3, 4/3]]; lambda = 1; solving
+    // (I + H^-1) @ gebv = [-1, 1] by hand gives gebv = [-1/3, 1/3].
+    assert!((parsed.gebv[0] - (-1.0 / 3.0)).abs() < 1e-4);
+    assert!((parsed.gebv[1] - (1.0 / 3.0)).abs() < 1e-4);
+
+    // diag(H^-1) = 4/3 -> pev = 1/(3/4 + 1) = 4/7 -> reliability = 3/7.
+    let expected_reliability = 3.0 / 7.0;
+    assert!((parsed.reliability[0] - expected_reliability).abs() < 1e-6);
+    assert!((parsed.reliability[1] - expected_reliability).abs() < 1e-6);
+    assert!((parsed.accuracy[0] - expected_reliability.sqrt()).abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+fn test_cross_validate_gblup_leave_one_out_folds_are_zero_variance() {
+    // k = n_individuals makes every fold a singleton (leave-one-out),
+    // regardless of the internal RNG's shuffle order: a 1-element
+    // observed/predicted pair always has zero variance, so
+    // pearson_correlation's zero-variance fallback fires deterministically
+    // for every fold.
+    let phenotypes = vec![10.0, 12.0, 11.0];
+    let grm = vec![
+        1.0, 0.2, 0.1,
+        0.2, 1.0, 0.3,
+        0.1, 0.3, 1.0,
+    ];
+    let result = cross_validate_gblup(&phenotypes, &grm, 3, 0.5, 3, 1);
+    let parsed: CrossValidationResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.fold_correlations.len(), 3);
+    for &r in &parsed.fold_correlations {
+        assert_eq!(r, 0.0);
+    }
+    assert_eq!(parsed.mean_accuracy, 0.0);
+    assert_eq!(parsed.std_accuracy, 0.0);
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_selection_index_hazel_single_trait_is_hand_derivable() {
+    // Single trait collapses P, G, and w to scalars: b = G*w/P = 0.5, so
+    // every downstream quantity (index values, ranking, sigma_i, accuracy)
+    // is a plain scalar computation. proportion_selected = 1.0 drives the
+    // truncation point to -infinity, making selection intensity exactly
+    // 0.0 without relying on the inverse-normal-CDF approximation's
+    // precision.
+    let trait_values = vec![10.0, 20.0];
+    let economic_weights = vec![1.0];
+    let phenotypic_cov = vec![4.0];
+    let genetic_cov = vec![2.0];
+    let result = calculate_selection_index_hazel(
+        &trait_values, &economic_weights, &phenotypic_cov, &genetic_cov, 2, 1, 1.0,
+    );
+    let parsed: HazelIndexResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.index_coefficients[0] - 0.5).abs() < 1e-9);
+    assert!((parsed.index_values[0] - 5.0).abs() < 1e-9);
+    assert!((parsed.index_values[1] - 10.0).abs() < 1e-9);
+    assert_eq!(parsed.rankings, vec![1, 0]);
+    assert!((parsed.expected_gain[0] - 0.0).abs() < 1e-9);
+    assert!((parsed.accuracy - 0.5_f64.sqrt()).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_estimate_gblup_ad_additive_only_matches_hand_solved_mme() {
+    // Additive-only mode never touches grm_d/homozygosity, so it reduces to
+    // the same (G + lambda*I) x = (y - mean) system as plain GBLUP, solved
+    // by hand below for a diagonally-dominant 2x2 case.
+    let phenotypes = vec![10.0, 12.0];
+    let grm_a = vec![
+        1.0, 0.5,
+        0.5, 1.0,
+    ];
+    let grm_d = vec![0.0, 0.0, 0.0, 0.0];
+    let homozygosity = vec![0.0, 0.0];
+    let result = estimate_gblup_ad(
+        &phenotypes, &grm_a, &grm_d, &homozygosity, 0.0, 2, 0.5, 0.0, GblupModel::Additive,
+    );
+    let parsed: GblupAdResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.mean - 11.0).abs() < 1e-9);
+    assert!((parsed.var_additive - 1.0).abs() < 1e-9);
+    assert!((parsed.var_dominance - 0.0).abs() < 1e-9);
+    assert!((parsed.inbreeding_depression - 0.0).abs() < 1e-9);
+
+    // (G + I) x = [-1, 1] solved by hand gives x = [-2/3, 2/3].
+    assert!((parsed.gebv[0] - (-2.0 / 3.0)).abs() < 1e-4);
+    assert!((parsed.gebv[1] - (2.0 / 3.0)).abs() < 1e-4);
+    assert!((parsed.gedd[0] - 0.0).abs() < 1e-9);
+    assert!((parsed.gedd[1] - 0.0).abs() < 1e-9);
+    assert!((parsed.getgv[0] - parsed.gebv[0]).abs() < 1e-9);
+    assert!((parsed.getgv[1] - parsed.gebv[1]).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_build_grm_van_raden1_two_individuals_is_hand_derivable() {
+    // 2 individuals, 2 markers, perfectly anti-correlated dosages: both
+    // markers have freq 0.5, so Z = [[-1,1],[1,-1]] and the VanRaden-1 GRM
+    // is exactly computable by hand.
+    let markers = vec![0, 2, 2, 0];
+    let result = build_grm(&markers, 2, 2, GrmMethod::VanRaden1);
+    let parsed: GRMResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.n_markers_used, 2);
+    assert!((parsed.matrix[0] - 2.0).abs() < 1e-9);
+    assert!((parsed.matrix[1] - (-2.0)).abs() < 1e-9);
+    assert!((parsed.matrix[3] - 2.0).abs() < 1e-9);
+    assert!((parsed.mean_diagonal - 2.0).abs() < 1e-9);
+    assert!((parsed.mean_off_diagonal - (-2.0)).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_build_relatedness_qg_derives_frequencies_from_data() {
+    // Same anti-correlated 2-individual/2-marker dosages as the build_grm
+    // case above: build_relatedness_qg must derive the same 0.5 allele
+    // frequencies internally, giving an exact r = -1 relatedness.
+    let genotypes = vec![0, 2, 2, 0];
+    let result = build_relatedness_qg(&genotypes, 2, 2);
+
+    assert!((result[0] - 1.0).abs() < 1e-9);
+    assert!((result[1] - (-1.0)).abs() < 1e-9);
+    assert!((result[2] - (-1.0)).abs() < 1e-9);
+    assert!((result[3] - 1.0).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_simulate_breeding_program_fixed_loci_generation_zero_is_exact() {
+    // initial_freqs of exactly 0.0/1.0 make the starting population
+    // deterministic regardless of the RNG draw (rng.gen::<f64>() < 1.0 is
+    // always true, < 0.0 is always false), and n_generations = 0 means no
+    // selection/mating RNG is exercised at all - only the fixed generation-0
+    // summary is returned.
+    let effect_sizes = vec![2.0, 3.0];
+    let initial_freqs = vec![1.0, 0.0];
+    let result = simulate_breeding_program(
+        3, 2, &effect_sizes, &initial_freqs, 0, 0.5, 0.0, 0.0, 0.0, 0.0, false,
+    );
+    let parsed: BreedingSimResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.n_loci, 2);
+    assert_eq!(parsed.mean_genetic_value.len(), 1);
+    // Every individual is fixed hap1=hap2=[1,0], so genomic value = 2*2 + 3*0 = 4.
+    assert!((parsed.mean_genetic_value[0] - 4.0).abs() < 1e-9);
+    assert!((parsed.additive_variance[0] - 0.0).abs() < 1e-9);
+    assert!((parsed.inbreeding_coefficient[0] - 0.0).abs() < 1e-9);
+    assert!((parsed.allele_freq_trajectory[0] - 1.0).abs() < 1e-9);
+    assert!((parsed.allele_freq_trajectory[1] - 0.0).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_adjust_for_ancestry_zero_pcs_is_plain_ols() {
+    // n_pcs = 0 (no ancestry axes) and no interaction collapses the design
+    // to [1, GEBV], an ordinary least-squares regression whose fit is
+    // hand-derivable for a perfectly linear phenotype/GEBV relationship.
+    let gebv = vec![1.0, 2.0, 3.0];
+    let phenotypes = vec![2.0, 4.0, 6.0]; // y = 2*gebv, exactly
+    let pcs: Vec<f64> = vec![];
+    let result = adjust_for_ancestry(&gebv, &phenotypes, &pcs, 3, 0, false);
+    let parsed: AncestryAdjustedResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!(parsed.pc_coefficients.is_empty());
+    assert!(parsed.interaction_coefficients.is_empty());
+    assert!((parsed.intercept - 0.0).abs() < 1e-6);
+    assert!((parsed.gebv_coefficient - 2.0).abs() < 1e-6);
+    assert!((parsed.adjusted_gebv[0] - 2.0).abs() < 1e-6);
+    assert!((parsed.adjusted_gebv[1] - 4.0).abs() < 1e-6);
+    assert!((parsed.adjusted_gebv[2] - 6.0).abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_fst_completely_fixed_populations_is_exactly_one() {
+    // Two populations fixed for opposite alleles (pop A all 0, pop B all 2):
+    // there's no within-population heterozygosity and the Weir & Cockerham
+    // variance components reduce to a=0.5, b=0, c=0, giving Fst=1, Fit=1,
+    // Fis=0 exactly.
+    let genotypes = vec![0, 0, 2, 2];
+    let population_ids = vec![0, 0, 1, 1];
+    let result = calculate_fst(&genotypes, &population_ids, 4, 1);
+    let parsed: FstResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!((parsed.fst - 1.0).abs() < 1e-9);
+    assert!((parsed.fit - 1.0).abs() < 1e-9);
+    assert!((parsed.fis - 0.0).abs() < 1e-9);
+    assert_eq!(parsed.per_marker_fst.len(), 1);
+    assert!((parsed.per_marker_fst[0] - 1.0).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_neutrality_tests_single_singleton_site() {
+    // 4 samples (n=8 chromosomes), 1 segregating site with a single
+    // heterozygote (a folded singleton, unpolarized): theta_w, theta_pi,
+    // and the Tajima's/Fu & Li statistics all reduce to closed-form
+    // expressions of n and s=1 alone, computed independently in Python and
+    // hardcoded here.
+    let genotypes = vec![1, 0, 0, 0];
+    let ancestral: Vec<i32> = vec![];
+    let result = calculate_neutrality_tests(&genotypes, 4, 1, &ancestral);
+    let parsed: NeutralityTestResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.n_segregating_sites, 1);
+    assert!(!parsed.ancestral_polarized);
+    assert!((parsed.theta_w - 0.38567493112947665).abs() < 1e-9);
+    assert!((parsed.theta_pi - 0.25).abs() < 1e-9);
+    assert!((parsed.tajimas_d - (-1.0548191070623705)).abs() < 1e-6);
+    assert!((parsed.fu_li_d - (-1.262084443631702)).abs() < 1e-6);
+    assert!((parsed.fu_li_f - (-1.5303122372309828)).abs() < 1e-6);
+    assert_eq!(parsed.fay_wu_h, 0.0);
+    assert_eq!(parsed.zeng_e, 0.0);
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_relatedness_dispatches_by_method_string() {
+    // Same anti-correlated 2-individual/2-marker dosages used for the
+    // build_grm/build_relatedness_qg tests above: this wrapper should
+    // reproduce exactly those hand-derived matrices depending on `method`.
+    let genotypes = vec![0, 2, 2, 0];
+
+    let van_raden = calculate_relatedness(&genotypes, 2, 2, "van_raden");
+    let van_raden_parsed: RelatednessResult = serde_wasm_bindgen::from_value(van_raden).unwrap();
+    assert_eq!(van_raden_parsed.method, "van_raden");
+    assert!((van_raden_parsed.relatedness_matrix[0] - 2.0).abs() < 1e-9);
+    assert!((van_raden_parsed.relatedness_matrix[1] - (-2.0)).abs() < 1e-9);
+
+    let qg = calculate_relatedness(&genotypes, 2, 2, "queller_goodnight");
+    let qg_parsed: RelatednessResult = serde_wasm_bindgen::from_value(qg).unwrap();
+    assert_eq!(qg_parsed.method, "queller_goodnight");
+    assert!((qg_parsed.relatedness_matrix[0] - 1.0).abs() < 1e-9);
+    assert!((qg_parsed.relatedness_matrix[1] - (-1.0)).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_simulate_population_zero_generations_is_the_all_zero_founders() {
+    // generations = 0 means the mutation/selection/mating loop never runs,
+    // so the returned state is exactly the hard-coded founder population
+    // (every haplotype all-zero) - deterministic regardless of the seeded
+    // RNG, since it's never drawn from.
+    let allele_effects = vec![1.0, 2.0];
+    let result = simulate_population(2, 2, 0, 0.01, &allele_effects, 1.0, 0.0, 0.0, 42);
+    let parsed: PopulationSimResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.n_loci, 2);
+    assert_eq!(parsed.mean_phenotype, vec![0.0]);
+    assert_eq!(parsed.additive_variance, vec![0.0]);
+    assert_eq!(parsed.expected_heterozygosity, vec![0.0]);
+    assert_eq!(parsed.allele_freq_trajectory, vec![0.0, 0.0]);
+    assert_eq!(parsed.final_genotypes, vec![0, 0, 0, 0]);
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_ld_three_identical_markers_binned_by_distance() {
+    // 3 markers with identical per-sample genotype columns (same complete-
+    // coupling-phase pattern as the calculate_ld_pair_em test above): every
+    // pairwise D/D'/r^2 is the same hand-derived [0.25, 1.0, 1.0], and
+    // positions [0, 10, 20] put the two distance-10 pairs in one decay bin
+    // and the one distance-20 pair in another.
+    let genotypes = vec![
+        2, 2, 2,
+        0, 0, 0,
+        2, 2, 2,
+        0, 0, 0,
+    ];
+    let positions = vec![0.0, 10.0, 20.0];
+    let result = calculate_ld(&genotypes, 4, 3, &positions, 10);
+    let parsed: LDGenomeResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.n_pairs_computed, 3);
+    assert_eq!(parsed.n_pairs_skipped, 0);
+    for pair in &parsed.pairs {
+        assert!((pair.d - 0.25).abs() < 1e-9);
+        assert!((pair.d_prime - 1.0).abs() < 1e-9);
+        assert!((pair.r_squared - 1.0).abs() < 1e-9);
+    }
+
+    assert_eq!(parsed.decay_curve.len(), 10);
+    assert_eq!(parsed.decay_curve[5].n_pairs, 2);
+    assert!((parsed.decay_curve[5].mean_r_squared - 1.0).abs() < 1e-9);
+    assert_eq!(parsed.decay_curve[9].n_pairs, 1);
+    assert!((parsed.decay_curve[9].mean_r_squared - 1.0).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_calculate_polygenic_score_no_pcs_is_plain_weighted_sum() {
+    // n_pcs = 0 skips ancestry adjustment entirely, so raw_score is just
+    // the hand-computable weighted dosage sum and adjusted_score is an
+    // identical copy.
+    let genotypes = vec![2, 0, 0, 2];
+    let effect_alleles = vec![1, 1];
+    let effect_sizes = vec![1.0, 2.0];
+    let pcs: Vec<f64> = vec![];
+    let result = calculate_polygenic_score(&genotypes, &effect_alleles, &effect_sizes, 2, 2, &pcs, 0, false);
+    let parsed: PolygenicScoreResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert!(!parsed.ancestry_adjusted);
+    assert!((parsed.raw_score[0] - 2.0).abs() < 1e-9); // 1*2 + 2*0
+    assert!((parsed.raw_score[1] - 4.0).abs() < 1e-9); // 1*0 + 2*2
+    assert_eq!(parsed.adjusted_score, parsed.raw_score);
+    assert!(parsed.pc_coefficients.is_empty());
+    assert!(parsed.interaction_coefficients.is_empty());
+    assert!((parsed.intercept - 0.0).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn test_needleman_wunsch_affine_single_deletion_pays_only_gap_open() {
+    // "AC" vs "A": the A's match (+1) and the trailing C is a 1-base
+    // deletion, costing only gap_open (2) since it's never extended.
+    // score = 1 - 2 = -1.
+    let result = needleman_wunsch_affine("AC", "A", 1, -1, 2, 1);
+    let parsed: AlignmentResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.score, -1);
+    assert_eq!(parsed.align1, "AC");
+    assert_eq!(parsed.align2, "A-");
+}
+
+#[wasm_bindgen_test]
+fn test_smith_waterman_affine_local_alignment_spans_a_single_deletion() {
+    // "AGA" vs "AA": the best local alignment isn't just the leading A-A
+    // match (score 2) — it pays gap_open (1) to delete the middle G and
+    // pick up the trailing A-A match too, for 2 - 1 + 2 = 3, more than
+    // either single match alone.
+    let result = smith_waterman_affine("AGA", "AA", 2, -1, 1, 1);
+    let parsed: AlignmentResult = serde_wasm_bindgen::from_value(result).unwrap();
+
+    assert_eq!(parsed.score, 3);
+    assert_eq!(parsed.align1, "AGA");
+    assert_eq!(parsed.align2, "A-A");
+}